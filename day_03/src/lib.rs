@@ -0,0 +1,230 @@
+use regex::Regex;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Number {
+    pub value: u32,
+    pub y: usize,
+    pub x_start: usize,
+    pub x_end: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol {
+    pub char: char,
+    pub x: usize,
+    pub y: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Aggregation {
+    // None once the result no longer fits a u128, which only a product of four or
+    // more ten-digit numbers can reach.
+    pub fn apply(&self, values: impl Iterator<Item = u32>) -> Option<u128> {
+        let mut values = values.map(u128::from);
+        match self {
+            Aggregation::Product => values.try_fold(1_u128, |product, value| product.checked_mul(value)),
+            Aggregation::Sum => values.try_fold(0_u128, |sum, value| sum.checked_add(value)),
+            Aggregation::Max => Some(values.max().unwrap_or(0)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct GearRule<'a> {
+    pub symbols: &'a [char],
+    pub neighbours: usize,
+    pub aggregation: Aggregation,
+}
+
+pub const STANDARD_GEAR: GearRule<'static> = GearRule {
+    symbols: &['*'],
+    neighbours: 2,
+    aggregation: Aggregation::Product,
+};
+
+#[derive(Debug)]
+pub struct Schematic {
+    pub numbers: Vec<Number>,
+    pub symbols: Vec<Symbol>,
+    number_symbols: Vec<Vec<usize>>,
+    symbol_numbers: Vec<Vec<usize>>,
+}
+
+impl Schematic {
+    pub fn parse(s: &str) -> Self {
+        let re = Regex::new(r"\d+").unwrap();
+        let numbers = s
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                re.find_iter(line).map(move |capture| Number {
+                    value: capture.as_str().parse().unwrap(),
+                    y,
+                    x_start: capture.start(),
+                    x_end: capture.end(),
+                })
+            })
+            .collect::<Vec<_>>();
+        let symbols = s
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| {
+                line.char_indices()
+                    .filter(|(_, char)| !(char.is_ascii_digit() || *char == '.'))
+                    .map(move |(x, char)| Symbol { char, x, y })
+            })
+            .collect::<Vec<_>>();
+
+        let symbol_ids: HashMap<[usize; 2], usize> = symbols
+            .iter()
+            .enumerate()
+            .map(|(id, symbol)| ([symbol.x, symbol.y], id))
+            .collect();
+        let mut number_symbols = vec![vec![]; numbers.len()];
+        let mut symbol_numbers = vec![vec![]; symbols.len()];
+        for (number_id, number) in numbers.iter().enumerate() {
+            for yi in number.y.saturating_sub(1)..=number.y + 1 {
+                for xi in number.x_start.saturating_sub(1)..=number.x_end {
+                    if let Some(&symbol_id) = symbol_ids.get(&[xi, yi]) {
+                        number_symbols[number_id].push(symbol_id);
+                        symbol_numbers[symbol_id].push(number_id);
+                    }
+                }
+            }
+        }
+
+        Schematic {
+            numbers,
+            symbols,
+            number_symbols,
+            symbol_numbers,
+        }
+    }
+
+    pub fn symbols_of(&self, number_id: usize) -> &[usize] {
+        &self.number_symbols[number_id]
+    }
+
+    pub fn numbers_of(&self, symbol_id: usize) -> &[usize] {
+        &self.symbol_numbers[symbol_id]
+    }
+
+    pub fn part_numbers(&self) -> impl Iterator<Item = &Number> + '_ {
+        self.numbers
+            .iter()
+            .enumerate()
+            .filter(|(number_id, _)| !self.number_symbols[*number_id].is_empty())
+            .map(|(_, number)| number)
+    }
+
+    pub fn gears<'a>(&'a self, rule: &'a GearRule) -> impl Iterator<Item = usize> + 'a {
+        self.symbols
+            .iter()
+            .enumerate()
+            .filter(move |(symbol_id, symbol)| {
+                rule.symbols.contains(&symbol.char)
+                    && self.symbol_numbers[*symbol_id].len() == rule.neighbours
+            })
+            .map(|(symbol_id, _)| symbol_id)
+    }
+
+    pub fn gear_ratios<'a>(&'a self, rule: &'a GearRule) -> impl Iterator<Item = Option<u128>> + 'a {
+        self.gears(rule)
+            .map(|symbol_id| rule.aggregation.apply(self.neighbour_values(symbol_id)))
+    }
+
+    pub fn gear_ratio_sum(&self, rule: &GearRule) -> Option<u128> {
+        self.gear_ratios(rule).try_fold(0_u128, |sum, ratio| sum.checked_add(ratio?))
+    }
+
+    pub fn neighbour_values(&self, symbol_id: usize) -> impl Iterator<Item = u32> + '_ {
+        self.symbol_numbers[symbol_id]
+            .iter()
+            .map(|&number_id| self.numbers[number_id].value)
+    }
+
+    pub fn symbol_sums(&self) -> Vec<u64> {
+        (0..self.symbols.len())
+            .map(|symbol_id| self.neighbour_values(symbol_id).map(u64::from).sum())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn it_builds_graph() {
+        let schematic = Schematic::parse(TEST_INPUT);
+        assert_eq!(schematic.numbers.len(), 10);
+        assert_eq!(schematic.symbols.len(), 6);
+        assert_eq!(schematic.symbols_of(0), &[0]);
+        assert!(schematic.symbols_of(1).is_empty());
+        assert_eq!(schematic.numbers_of(0), &[0, 2]);
+        assert_eq!(schematic.gears(&STANDARD_GEAR).collect::<Vec<_>>(), vec![0, 5]);
+        assert_eq!(schematic.symbol_sums(), vec![502, 633, 617, 592, 664, 1353]);
+    }
+
+    #[test]
+    fn it_applies_gear_rules() {
+        let schematic = Schematic::parse(TEST_INPUT);
+        assert_eq!(schematic.gear_ratio_sum(&STANDARD_GEAR), Some(467835));
+        let any_single = GearRule {
+            symbols: &['#', '+', '$', '*'],
+            neighbours: 1,
+            aggregation: Aggregation::Max,
+        };
+        assert_eq!(schematic.gear_ratio_sum(&any_single), Some(633 + 617 + 592 + 664));
+        let sum_pairs = GearRule {
+            aggregation: Aggregation::Sum,
+            ..STANDARD_GEAR
+        };
+        assert_eq!(schematic.gear_ratio_sum(&sum_pairs), Some(502 + 1353));
+
+        let triple = Schematic::parse("1.2\n.#.\n3..");
+        let rule = GearRule {
+            symbols: &['#'],
+            neighbours: 3,
+            aggregation: Aggregation::Product,
+        };
+        assert_eq!(triple.gear_ratio_sum(&rule), Some(6));
+    }
+
+    #[test]
+    fn it_handles_large_neighbours() {
+        let triple = Schematic::parse("4000000.3000000\n.......#.......\n2000000........");
+        let rule = GearRule {
+            symbols: &['#'],
+            neighbours: 3,
+            aggregation: Aggregation::Product,
+        };
+        assert_eq!(triple.gear_ratio_sum(&rule), Some(24_000_000_000_000_000_000));
+
+        let row = "4000000000.4000000000";
+        let six = Schematic::parse(&format!("{row}\n4000000000#4000000000\n{row}"));
+        let rule = GearRule { neighbours: 6, ..rule };
+        assert_eq!(six.gear_ratio_sum(&rule), None);
+        let rule = GearRule { aggregation: Aggregation::Sum, ..rule };
+        assert_eq!(six.gear_ratio_sum(&rule), Some(24_000_000_000));
+        assert_eq!(six.symbol_sums(), vec![24_000_000_000]);
+    }
+}
@@ -1,24 +1,9 @@
-use std::collections::HashSet;
-
-fn count_winning_points(line: &str) -> u32 {
-    println!("{line}");
-    let mut parts = line.split('|');
-    let (winning, mine) = (parts.next().unwrap(), parts.last().unwrap());
-    let winning_set: HashSet<u32> = winning.split_whitespace().map(|num| num.parse().unwrap()).collect();
-    let mine_iter = mine.split_whitespace().map(|num| num.parse::<u32>().unwrap());
-    let power = mine_iter
-        .filter(|num| winning_set.contains(num))
-        .count() as u32;
-    println!("power = {power}");
-    if power != 0 { 2u32.pow(power - 1) } else { 0 } }
+use day_04::Card;
 
 fn process_lottery_ticket(ticket: &str) -> u32 {
     ticket
         .lines()
-        .map(|line| {
-            let line = line.split(": ").last().unwrap();
-            count_winning_points(line)
-        })
+        .map(|line| Card::parse(line).unwrap().points())
         .sum()
 }
 
@@ -1,38 +1,10 @@
-use std::collections::HashSet;
+use day_04::{parse_deck, simulate, CascadeRule};
 
-fn count_winning_points(line: &str) -> usize {
-    let line = line.split(": ").last().unwrap();
-    let mut parts = line.split('|');
-    let (winning, mine) = (parts.next().unwrap(), parts.last().unwrap());
-    let winning_set: HashSet<u32> = winning.split_whitespace().map(|num| num.parse().unwrap()).collect();
-    let mine_iter = mine.split_whitespace().map(|num| num.parse::<u32>().unwrap());
-    mine_iter
-        .filter(|num| winning_set.contains(num))
-        .count()
+fn process_lottery_ticket(ticket: &str) -> u64 {
+    let deck = parse_deck(ticket).unwrap();
+    simulate(&deck, CascadeRule::default()).unwrap().total().unwrap()
 }
 
-fn process_lottery_ticket(ticket: &str) -> usize {
-    let lines = ticket.lines();
-    let n = lines.clone().count();
-    let mut points = vec![1; n];
-    let mut res: usize = 0;
-    for (i, line) in lines.enumerate() {
-        let point = points[i];
-        res += point;
-        let round = count_winning_points(line);
-        for j in 0_usize..round {
-            let index = i + j + 1;
-            if index < n {
-                points[i + j + 1] += point;
-            } else {
-                break
-            }
-        }
-    }
-    res
-}
-
-
 fn main() {
     let input = include_str!("./input.txt");
     let res = process_lottery_ticket(input);
@@ -0,0 +1,384 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::io::BufRead;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScratchError {
+    Parse(String),
+    DuplicateId(u32),
+    OutOfDeck { card_id: u32, offset: usize },
+    Overflow { card_id: u32 },
+    Io(String),
+}
+
+impl fmt::Display for ScratchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScratchError::Parse(line) => write!(f, "can't parse card: {line:?}"),
+            ScratchError::DuplicateId(id) => write!(f, "card {id} appears more than once"),
+            ScratchError::OutOfDeck { card_id, offset } => {
+                write!(f, "card {card_id} wins a copy {offset} cards past the end of the deck")
+            }
+            ScratchError::Overflow { card_id } => write!(f, "copy counts overflow u64 at card {card_id}"),
+            ScratchError::Io(err) => write!(f, "can't read cards: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ScratchError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Card {
+    pub id: u32,
+    pub matches: usize,
+}
+
+impl Card {
+    pub fn parse(line: &str) -> Result<Self, ScratchError> {
+        let err = || ScratchError::Parse(line.to_string());
+        let (head, numbers) = line.split_once(':').ok_or_else(err)?;
+        let id = head
+            .strip_prefix("Card")
+            .and_then(|id| id.trim().parse().ok())
+            .ok_or_else(err)?;
+        let (winning, mine) = numbers.split_once('|').ok_or_else(err)?;
+        let winning_set = winning
+            .split_whitespace()
+            .map(|num| num.parse::<u32>().map_err(|_| err()))
+            .collect::<Result<HashSet<_>, _>>()?;
+        let mut matches = 0;
+        for num in mine.split_whitespace() {
+            if winning_set.contains(&num.parse::<u32>().map_err(|_| err())?) {
+                matches += 1;
+            }
+        }
+        Ok(Card { id, matches })
+    }
+
+    pub fn points(&self) -> u32 {
+        if self.matches != 0 { 2u32.pow(self.matches as u32 - 1) } else { 0 }
+    }
+}
+
+// What happens to copies won past the last card of the deck.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Overflow {
+    #[default]
+    Drop,
+    Clamp,
+    Wrap,
+    Error,
+}
+
+// Whether "the next N cards" means the next N cards of the deck or ids `id + 1..=id + N`,
+// in which case missing ids inside the deck simply win nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Addressing {
+    #[default]
+    Position,
+    Id,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CascadeRule {
+    pub overflow: Overflow,
+    pub addressing: Addressing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cascade {
+    pub ids: Vec<u32>,
+    pub copies: Vec<u64>,
+}
+
+impl Cascade {
+    pub fn total(&self) -> Result<u64, ScratchError> {
+        self.ids.iter().zip(&self.copies).try_fold(0_u64, |total, (&card_id, &copies)| {
+            total.checked_add(copies).ok_or(ScratchError::Overflow { card_id })
+        })
+    }
+
+    pub fn copies_of(&self, id: u32) -> Option<u64> {
+        self.ids
+            .binary_search(&id)
+            .ok()
+            .map(|position| self.copies[position])
+    }
+}
+
+pub fn parse_deck(s: &str) -> Result<Vec<Card>, ScratchError> {
+    s.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(Card::parse)
+        .collect()
+}
+
+// Cards are processed once each in id order, so copies wrapped onto an already
+// processed card are counted but don't cascade any further.
+pub fn simulate(cards: &[Card], rule: CascadeRule) -> Result<Cascade, ScratchError> {
+    let mut cards = cards.to_vec();
+    cards.sort_by_key(|card| card.id);
+    if let Some(pair) = cards.windows(2).find(|pair| pair[0].id == pair[1].id) {
+        return Err(ScratchError::DuplicateId(pair[0].id));
+    }
+    let n = cards.len();
+    let positions: HashMap<u32, usize> = cards
+        .iter()
+        .enumerate()
+        .map(|(position, card)| (card.id, position))
+        .collect();
+    let (first_id, last_id) = match (cards.first(), cards.last()) {
+        (Some(first), Some(last)) => (first.id as usize, last.id as usize),
+        _ => return Ok(Cascade { ids: vec![], copies: vec![] }),
+    };
+
+    let mut copies = vec![1u64; n];
+    for (i, card) in cards.iter().enumerate() {
+        let point = copies[i];
+        for offset in 1..=card.matches {
+            let (target, last) = match rule.addressing {
+                Addressing::Position => (i + offset, n - 1),
+                Addressing::Id => (card.id as usize + offset, last_id),
+            };
+            let target = if target <= last {
+                target
+            } else {
+                match rule.overflow {
+                    Overflow::Drop => break,
+                    Overflow::Clamp => last,
+                    Overflow::Wrap => match rule.addressing {
+                        Addressing::Position => target % n,
+                        Addressing::Id => first_id + (target - first_id) % (last_id - first_id + 1),
+                    },
+                    Overflow::Error => {
+                        return Err(ScratchError::OutOfDeck { card_id: card.id, offset });
+                    }
+                }
+            };
+            let position = match rule.addressing {
+                Addressing::Position => Some(target),
+                Addressing::Id => positions.get(&(target as u32)).copied(),
+            };
+            if let Some(position) = position {
+                copies[position] = copies[position]
+                    .checked_add(point)
+                    .ok_or(ScratchError::Overflow { card_id: card.id })?;
+            }
+        }
+    }
+
+    Ok(Cascade {
+        ids: cards.iter().map(|card| card.id).collect(),
+        copies,
+    })
+}
+
+// Streams the stock cascade (next cards in deck order, overflow dropped) card by
+// card, only keeping pending copies for as many cards ahead as the best card wins.
+pub struct CardStream<R> {
+    reader: R,
+    line: String,
+    pending: VecDeque<u64>,
+    overflowed: bool,
+}
+
+impl<R: BufRead> CardStream<R> {
+    pub fn new(reader: R) -> Self {
+        CardStream {
+            reader,
+            line: String::new(),
+            pending: VecDeque::new(),
+            overflowed: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for CardStream<R> {
+    type Item = Result<(u32, u64), ScratchError>;
+
+    // Pending copies are meaningless after an overflow, so the stream ends there.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.overflowed {
+            return None;
+        }
+        loop {
+            self.line.clear();
+            match self.reader.read_line(&mut self.line) {
+                Ok(0) => return None,
+                Ok(_) if self.line.trim().is_empty() => continue,
+                Ok(_) => break,
+                Err(err) => return Some(Err(ScratchError::Io(err.to_string()))),
+            }
+        }
+        let card = match Card::parse(self.line.trim_end()) {
+            Ok(card) => card,
+            Err(err) => return Some(Err(err)),
+        };
+        let card_id = card.id;
+        let mut overflow = || {
+            self.overflowed = true;
+            ScratchError::Overflow { card_id }
+        };
+        let copies = match self.pending.pop_front().unwrap_or(0).checked_add(1) {
+            Some(copies) => copies,
+            None => return Some(Err(overflow())),
+        };
+        if self.pending.len() < card.matches {
+            self.pending.resize(card.matches, 0);
+        }
+        for pending in self.pending.iter_mut().take(card.matches) {
+            match pending.checked_add(copies) {
+                Some(sum) => *pending = sum,
+                None => return Some(Err(overflow())),
+            }
+        }
+        Some(Ok((card.id, copies)))
+    }
+}
+
+pub fn stream_total<R: BufRead>(reader: R) -> Result<u64, ScratchError> {
+    CardStream::new(reader).try_fold(0_u64, |total, card| {
+        let (card_id, copies) = card?;
+        total.checked_add(copies).ok_or(ScratchError::Overflow { card_id })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    #[test]
+    fn it_simulates_cascade() {
+        let deck = parse_deck(TEST_INPUT).unwrap();
+        let cascade = simulate(&deck, CascadeRule::default()).unwrap();
+        assert_eq!(cascade.copies, vec![1, 2, 4, 8, 14, 1]);
+        assert_eq!(cascade.total(), Ok(30));
+        assert_eq!(cascade.copies_of(5), Some(14));
+    }
+
+    #[test]
+    fn it_handles_overflow() {
+        let deck = parse_deck("Card 1: 1 | 2\nCard 2: 1 2 3 | 1 2 3").unwrap();
+        let rule = |overflow| CascadeRule { overflow, addressing: Addressing::Position };
+        assert_eq!(simulate(&deck, rule(Overflow::Drop)).unwrap().copies, vec![1, 1]);
+        assert_eq!(simulate(&deck, rule(Overflow::Clamp)).unwrap().copies, vec![1, 4]);
+        assert_eq!(simulate(&deck, rule(Overflow::Wrap)).unwrap().copies, vec![3, 2]);
+        assert_eq!(
+            simulate(&deck, rule(Overflow::Error)),
+            Err(ScratchError::OutOfDeck { card_id: 2, offset: 1 })
+        );
+    }
+
+    fn dense_deck(cards: u32) -> String {
+        (1..=cards).map(|id| format!("Card {id}: 1 2 | 1 2\n")).collect()
+    }
+
+    #[test]
+    fn it_reports_copy_overflow() {
+        let deck = parse_deck(&dense_deck(200)).unwrap();
+        assert_eq!(simulate(&deck, CascadeRule::default()), Err(ScratchError::Overflow { card_id: 91 }));
+        let deck = parse_deck(&dense_deck(60)).unwrap();
+        let cascade = simulate(&deck, CascadeRule::default()).unwrap();
+        assert_eq!(cascade.copies[59], cascade.copies[58] + cascade.copies[57] + 1);
+        let clamp = CascadeRule { overflow: Overflow::Clamp, addressing: Addressing::Position };
+        let deck = parse_deck("Card 1: 1 | 1\nCard 2: 1 2 3 4 5 6 7 8 | 1 2 3 4 5 6 7 8").unwrap();
+        assert_eq!(simulate(&deck, clamp).unwrap().copies, vec![1, 2 + 2 * 8]);
+        let cascade = Cascade { ids: vec![1, 2], copies: vec![u64::MAX, 1] };
+        assert_eq!(cascade.total(), Err(ScratchError::Overflow { card_id: 2 }));
+    }
+
+    #[test]
+    fn it_handles_sparse_ids() {
+        let deck = parse_deck("Card 7: 1 2 | 1 2\nCard 3: 5 | 5\nCard 8: 1 | 2").unwrap();
+        let by_position = simulate(&deck, CascadeRule::default()).unwrap();
+        assert_eq!(by_position.ids, vec![3, 7, 8]);
+        assert_eq!(by_position.copies, vec![1, 2, 3]);
+        let by_id = CascadeRule { overflow: Overflow::Drop, addressing: Addressing::Id };
+        assert_eq!(simulate(&deck, by_id).unwrap().copies, vec![1, 1, 2]);
+        assert_eq!(
+            simulate(&parse_deck("Card 1: 1 | 1\nCard 1: 2 | 2").unwrap(), by_id),
+            Err(ScratchError::DuplicateId(1))
+        );
+    }
+
+    #[test]
+    fn it_streams_cards() {
+        let streamed = CardStream::new(TEST_INPUT.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(streamed, vec![(1, 1), (2, 2), (3, 4), (4, 8), (5, 14), (6, 1)]);
+        assert_eq!(stream_total(TEST_INPUT.as_bytes()), Ok(30));
+        assert!(matches!(
+            stream_total("Card 1: 1 | 1\nCard 2 1 | 1".as_bytes()),
+            Err(ScratchError::Parse(_))
+        ));
+    }
+
+    struct GeneratedDeck {
+        next_id: u32,
+        last_id: u32,
+        line: Vec<u8>,
+    }
+
+    impl GeneratedDeck {
+        fn new(last_id: u32) -> Self {
+            GeneratedDeck { next_id: 1, last_id, line: vec![] }
+        }
+    }
+
+    impl std::io::Read for GeneratedDeck {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.line.is_empty() && self.next_id <= self.last_id {
+                let id = self.next_id;
+                let mine = match id {
+                    id if id % 1000 == 0 => "1 2 3",
+                    id if id % 2 == 0 => "1",
+                    _ => "9",
+                };
+                self.line = format!("Card {id}: 1 2 3 | {mine}\n").into_bytes();
+                self.next_id += 1;
+            }
+            let n = buf.len().min(self.line.len());
+            buf[..n].copy_from_slice(&self.line[..n]);
+            self.line.drain(..n);
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn it_streams_large_deck() {
+        let deck = std::io::BufReader::new(GeneratedDeck::new(200_000));
+        let mut stream = CardStream::new(deck);
+        let mut total = 0;
+        while let Some(card) = stream.next() {
+            total += card.unwrap().1;
+            assert!(stream.pending.len() <= 3);
+        }
+        assert!(total > 200_000);
+
+        let mut lines = String::new();
+        std::io::Read::read_to_string(&mut GeneratedDeck::new(5000), &mut lines).unwrap();
+        let expected = simulate(&parse_deck(&lines).unwrap(), CascadeRule::default()).unwrap();
+        let deck = std::io::BufReader::new(GeneratedDeck::new(5000));
+        assert_eq!(stream_total(deck), expected.total());
+    }
+
+    #[test]
+    fn it_reports_stream_overflow() {
+        let deck = dense_deck(200);
+        assert_eq!(stream_total(deck.as_bytes()), Err(ScratchError::Overflow { card_id: 90 }));
+        let streamed = CardStream::new(deck.as_bytes()).skip(89).collect::<Vec<_>>();
+        assert_eq!(streamed.len(), 2);
+        assert_eq!(streamed[1], Err(ScratchError::Overflow { card_id: 91 }));
+        let deck = dense_deck(60);
+        let expected = simulate(&parse_deck(&deck).unwrap(), CascadeRule::default()).unwrap();
+        assert_eq!(stream_total(deck.as_bytes()), expected.total());
+    }
+}
@@ -1,41 +1,8 @@
-use nom::IResult;
-use nom::character::complete::{space1, i64 as d};
-use nom::sequence::tuple;
-use std::ops::Range;
+use day_05::Almanac;
 
-
-fn parse_numbers(input: &str) -> IResult<&str, (i64, i64, i64)> {
-    let (input, (a, _, b, _, c)) = tuple((d, space1, d, space1, d))(input)?;
-
-    Ok((input, (a, b, c)))
-}
-
-fn process_map_block(block: &str) -> Vec<(Range<i64>, i64)> {
-    let mut lines = block.lines();
-    let _map_name = lines.next().unwrap();
-    lines.map(|line| {
-        let (_, (destination_start, source_start, range_len)) = parse_numbers(line).unwrap();
-        let diff = destination_start - source_start;
-        let range = source_start..(source_start + range_len);
-        (range, diff)
-    }).collect()
-}
 fn find_closest_location(s: &str) -> i64 {
-    let mut line_blocks = s.split("\n\n");
-    let seeds_str = line_blocks.next().unwrap().trim().split(": ").last().unwrap();
-    let mut seeds: Vec<i64> = seeds_str.split_whitespace().map(|seed| seed.parse().unwrap()).collect();
-    let maps = line_blocks.map(process_map_block).collect::<Vec<_>>();
-    *seeds.iter_mut().map(|seed| {
-        for map in maps.iter() {
-            for (range, diff) in map.iter() {
-                if range.contains(seed) {
-                    *seed += diff;
-                    break;
-                }
-            }
-        }
-        seed
-    }).min().unwrap()
+    let almanac = Almanac::parse(s);
+    almanac.seeds.iter().map(|&seed| almanac.location(seed)).min().unwrap()
 }
 
 fn main() {
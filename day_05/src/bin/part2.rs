@@ -1,52 +1,20 @@
-use nom::IResult;
-use nom::character::complete::{space1, i64 as d};
-use nom::sequence::tuple;
-use itertools::Itertools;
-use core::ops::Range;
+use day_05::Almanac;
 
-
-fn parse_numbers(input: &str) -> IResult<&str, (i64, i64, i64)> {
-    let (input, (a, _, b, _, c)) = tuple((d, space1, d, space1, d))(input)?;
-
-    Ok((input, (a, b, c)))
+fn find_closest_location(s: &str) -> i64 {
+    let almanac = Almanac::parse(s);
+    almanac.closest_location(&almanac.seed_ranges()).unwrap()
 }
 
-fn process_map_block(block: &str) -> Vec<(Range<i64>, i64)> {
-    let mut lines = block.lines();
-    let _map_name = lines.next().unwrap();
-    lines.map(|line| {
-        let (_, (destination_start, source_start, range_len)) = parse_numbers(line).unwrap();
-        let diff = destination_start - source_start;
-        let range = source_start..(source_start + range_len);
-        (range, diff)
-    }).collect()
-}
-fn find_closest_location(s: &str) -> i64 {
-    let mut line_blocks = s.split("\n\n");
-    let seeds_str = line_blocks.next().unwrap().trim().split(": ").last().unwrap();
-    let seeds: Vec<i64> = seeds_str.split_whitespace().map(|seed| seed.parse().unwrap()).collect();
-    let seed_ranges: Vec<Range<i64>> = seeds.into_iter()
-        .tuples()
-        .map(|(seed, range_len)| seed..(seed + range_len))
-        .collect();
-    let maps: Vec<Vec<(Range<i64>, i64)>> = line_blocks.map(process_map_block).collect();
-    let mut cur;
-    let mut res = i64::MAX;
-    for seed_range in seed_ranges {
-        for seed in seed_range {
-            cur = seed;
-            for map in maps.iter() {
-                for (range, diff) in map.iter() {
-                    if range.contains(&cur) {
-                        cur += diff;
-                        break;
-                    }
-                }
-            }
-            res = res.min(cur);
-        }
-    };
-    res
+#[cfg(test)]
+fn find_closest_location_per_seed(s: &str) -> i64 {
+    let almanac = Almanac::parse(s);
+    almanac
+        .seed_ranges()
+        .into_iter()
+        .flatten()
+        .map(|seed| almanac.location(seed))
+        .min()
+        .unwrap()
 }
 
 fn main() {
@@ -96,5 +64,6 @@ humidity-to-location map:
 60 56 37
 56 93 4";
         assert_eq!(find_closest_location(test_input), 46);
+        assert_eq!(find_closest_location_per_seed(test_input), 46);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,448 @@
+use nom::IResult;
+use nom::character::complete::{space1, i64 as d};
+use nom::sequence::tuple;
+use itertools::Itertools;
+use core::ops::Range;
+
+fn parse_numbers(input: &str) -> IResult<&str, (i64, i64, i64)> {
+    let (input, (a, _, b, _, c)) = tuple((d, space1, d, space1, d))(input)?;
+
+    Ok((input, (a, b, c)))
+}
+
+fn process_map_block(block: &str) -> Stage {
+    let mut lines = block.lines();
+    let map_name = lines.next().unwrap().trim_end_matches(" map:");
+    let (from, to) = map_name.split_once("-to-").unwrap();
+    let entries = lines.map(|line| {
+        let (_, (destination_start, source_start, range_len)) = parse_numbers(line).unwrap();
+        let diff = destination_start - source_start;
+        let range = source_start..(source_start + range_len);
+        (range, diff)
+    }).collect();
+    Stage { from: from.to_string(), to: to.to_string(), entries }
+}
+
+fn normalize(mut ranges: Vec<Range<i64>>) -> Vec<Range<i64>> {
+    ranges.retain(|range| !range.is_empty());
+    ranges.sort_by_key(|range| range.start);
+    let mut res: Vec<Range<i64>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match res.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => res.push(range),
+        }
+    }
+    res
+}
+
+fn subtract(ranges: &[Range<i64>], cut: &Range<i64>) -> Vec<Range<i64>> {
+    ranges
+        .iter()
+        .flat_map(|range| [range.start..range.end.min(cut.start), range.start.max(cut.end)..range.end])
+        .filter(|range| !range.is_empty())
+        .collect()
+}
+
+fn intersect(ranges: &[Range<i64>], other: &Range<i64>) -> Vec<Range<i64>> {
+    ranges
+        .iter()
+        .map(|range| range.start.max(other.start)..range.end.min(other.end))
+        .filter(|range| !range.is_empty())
+        .collect()
+}
+
+fn map_stage(map: &[(Range<i64>, i64)], ranges: &[Range<i64>]) -> Vec<Range<i64>> {
+    let mut res = vec![];
+    let mut unmapped = ranges.to_vec();
+    for (source, diff) in map {
+        let mut rest = vec![];
+        for range in unmapped {
+            let start = range.start.max(source.start);
+            let end = range.end.min(source.end);
+            if start < end {
+                res.push(start + diff..end + diff);
+                rest.push(range.start..start);
+                rest.push(end..range.end);
+            } else {
+                rest.push(range);
+            }
+        }
+        unmapped = rest.into_iter().filter(|range| !range.is_empty()).collect();
+    }
+    res.extend(unmapped);
+    normalize(res)
+}
+
+fn unmap_stage(map: &[(Range<i64>, i64)], ranges: &[Range<i64>]) -> Vec<Range<i64>> {
+    let mut res = vec![];
+    for (i, (source, diff)) in map.iter().enumerate() {
+        let shifted = ranges.iter().map(|range| range.start - diff..range.end - diff).collect::<Vec<_>>();
+        let hits = intersect(&shifted, source);
+        let unshadowed = map[..i].iter().fold(hits, |hits, (earlier, _)| subtract(&hits, earlier));
+        res.extend(unshadowed);
+    }
+    let identity = map.iter().fold(ranges.to_vec(), |ranges, (source, _)| subtract(&ranges, source));
+    res.extend(identity);
+    normalize(res)
+}
+
+#[derive(Debug, Clone)]
+pub struct Stage {
+    pub from: String,
+    pub to: String,
+    pub entries: Vec<(Range<i64>, i64)>,
+}
+
+impl Stage {
+    fn prioritized(&self, resolution: Resolution) -> Vec<(Range<i64>, i64)> {
+        match resolution {
+            Resolution::LastWins => self.entries.iter().rev().cloned().collect(),
+            Resolution::FirstWins | Resolution::Error => self.entries.clone(),
+        }
+    }
+
+    // In `Error` mode a value covered by two entries is reported instead of resolved.
+    pub fn lookup(&self, value: i64, resolution: Resolution) -> Result<(i64, Option<usize>), Issue> {
+        let mut hits = (0..self.entries.len()).filter(|&entry| self.entries[entry].0.contains(&value));
+        let entry = match resolution {
+            Resolution::FirstWins => hits.next(),
+            Resolution::LastWins => hits.next_back(),
+            Resolution::Error => match (hits.next(), hits.next()) {
+                (Some(i), Some(j)) => {
+                    let range = intersect(std::slice::from_ref(&self.entries[i].0), &self.entries[j].0).remove(0);
+                    return Err(Issue::SourceOverlap { entries: (i, j), range });
+                }
+                (entry, _) => entry,
+            },
+        };
+        Ok(match entry {
+            Some(entry) => (value + self.entries[entry].1, Some(entry)),
+            None => (value, None),
+        })
+    }
+
+    pub fn check(&self) -> Vec<Issue> {
+        let mut res = vec![];
+        for (i, (source, diff)) in self.entries.iter().enumerate() {
+            let destination = source.start + diff..source.end + diff;
+            for (j, (other_source, other_diff)) in self.entries.iter().enumerate().skip(i + 1) {
+                let overlap = intersect(std::slice::from_ref(source), other_source);
+                if let Some(range) = overlap.into_iter().next() {
+                    res.push(Issue::SourceOverlap { entries: (i, j), range });
+                }
+                let other_destination = other_source.start + other_diff..other_source.end + other_diff;
+                let overlap = intersect(std::slice::from_ref(&destination), &other_destination);
+                if let Some(range) = overlap.into_iter().next() {
+                    res.push(Issue::DestinationOverlap { entries: (i, j), range });
+                }
+            }
+        }
+        // Values outside every entry map to themselves, so they collide with any
+        // destination that lands outside the sources.
+        for (i, (source, diff)) in self.entries.iter().enumerate() {
+            let destination = source.start + diff..source.end + diff;
+            let identity = self
+                .entries
+                .iter()
+                .fold(vec![destination], |ranges, (other, _)| subtract(&ranges, other));
+            res.extend(identity.into_iter().map(|range| Issue::IdentityOverlap { entry: i, range }));
+        }
+        let covered = normalize(self.entries.iter().map(|(source, _)| source.clone()).collect());
+        res.extend(covered.windows(2).map(|pair| Issue::Gap { range: pair[0].end..pair[1].start }));
+        res
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Resolution {
+    #[default]
+    FirstWins,
+    LastWins,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Issue {
+    SourceOverlap { entries: (usize, usize), range: Range<i64> },
+    DestinationOverlap { entries: (usize, usize), range: Range<i64> },
+    IdentityOverlap { entry: usize, range: Range<i64> },
+    Gap { range: Range<i64> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep<'a> {
+    pub category: &'a str,
+    pub value: i64,
+    pub entry: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Almanac {
+    pub seeds: Vec<i64>,
+    stages: Vec<Stage>,
+    resolution: Resolution,
+}
+
+impl Almanac {
+    pub fn parse(s: &str) -> Self {
+        let mut line_blocks = s.split("\n\n");
+        let seeds_str = line_blocks.next().unwrap().trim().split(": ").last().unwrap();
+        let seeds = seeds_str.split_whitespace().map(|seed| seed.parse().unwrap()).collect();
+        let stages = line_blocks.map(process_map_block).collect();
+        Almanac { seeds, stages, resolution: Resolution::default() }
+    }
+
+    pub fn with_resolution(self, resolution: Resolution) -> Result<Self, (usize, Issue)> {
+        if resolution == Resolution::Error {
+            let overlap = self.check().into_iter().find(|(_, issue)| matches!(issue, Issue::SourceOverlap { .. }));
+            if let Some(overlap) = overlap {
+                return Err(overlap);
+            }
+        }
+        Ok(Almanac { resolution, ..self })
+    }
+
+    pub fn stages(&self) -> &[Stage] {
+        &self.stages
+    }
+
+    pub fn resolution(&self) -> Resolution {
+        self.resolution
+    }
+
+    // Stages and resolution are only set through `with_resolution`, which rejects
+    // overlapping sources in `Error` mode, so lookups here cannot fail.
+    fn lookup(&self, stage: &Stage, value: i64) -> (i64, Option<usize>) {
+        stage.lookup(value, self.resolution).expect("overlaps are rejected by with_resolution")
+    }
+
+    pub fn check(&self) -> Vec<(usize, Issue)> {
+        self.stages
+            .iter()
+            .enumerate()
+            .flat_map(|(i, stage)| stage.check().into_iter().map(move |issue| (i, issue)))
+            .collect()
+    }
+
+    pub fn seed_ranges(&self) -> Vec<Range<i64>> {
+        self.seeds
+            .iter()
+            .tuples()
+            .map(|(&seed, &range_len)| seed..(seed + range_len))
+            .collect()
+    }
+
+    pub fn location(&self, seed: i64) -> i64 {
+        self.stages.iter().fold(seed, |cur, stage| self.lookup(stage, cur).0)
+    }
+
+    pub fn trace(&self, seed: i64) -> Vec<TraceStep<'_>> {
+        let mut cur = seed;
+        let mut res = vec![];
+        if let Some(first) = self.stages.first() {
+            res.push(TraceStep { category: &first.from, value: cur, entry: None });
+        }
+        for stage in self.stages.iter() {
+            let (value, entry) = self.lookup(stage, cur);
+            cur = value;
+            res.push(TraceStep { category: &stage.to, value, entry });
+        }
+        res
+    }
+
+    pub fn location_ranges(&self, seed_ranges: &[Range<i64>]) -> Vec<Range<i64>> {
+        self.stages
+            .iter()
+            .fold(normalize(seed_ranges.to_vec()), |ranges, stage| map_stage(&stage.prioritized(self.resolution), &ranges))
+    }
+
+    pub fn seed_ranges_for(&self, location_ranges: &[Range<i64>]) -> Vec<Range<i64>> {
+        self.stages
+            .iter()
+            .rev()
+            .fold(normalize(location_ranges.to_vec()), |ranges, stage| unmap_stage(&stage.prioritized(self.resolution), &ranges))
+    }
+
+    pub fn seeds_for_location(&self, location: i64) -> Vec<i64> {
+        self.seed_ranges_for(std::slice::from_ref(&(location..location + 1)))
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    pub fn closest_seed(&self, seed_ranges: &[Range<i64>]) -> Option<(i64, Vec<TraceStep<'_>>)> {
+        let location = self.closest_location(seed_ranges)?;
+        let seed_ranges = normalize(seed_ranges.to_vec());
+        let seed = self
+            .seeds_for_location(location)
+            .into_iter()
+            .find(|seed| seed_ranges.iter().any(|range| range.contains(seed)))?;
+        Some((seed, self.trace(seed)))
+    }
+
+    pub fn closest_location(&self, seed_ranges: &[Range<i64>]) -> Option<i64> {
+        self.location_ranges(seed_ranges).first().map(|range| range.start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 3
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn it_maps_ranges() {
+        let almanac = Almanac::parse(TEST_INPUT);
+        let seed_ranges = almanac.seed_ranges();
+        assert_eq!(almanac.closest_location(&seed_ranges), Some(46));
+
+        let per_seed = normalize(
+            seed_ranges
+                .iter()
+                .flat_map(|range| range.clone())
+                .map(|seed| almanac.location(seed))
+                .map(|location| location..location + 1)
+                .collect(),
+        );
+        assert_eq!(almanac.location_ranges(&seed_ranges), per_seed);
+
+        let everything = [0..60, 60..120];
+        let per_seed = normalize((0..120).map(|seed| almanac.location(seed)).map(|l| l..l + 1).collect());
+        assert_eq!(almanac.location_ranges(&everything), per_seed);
+    }
+
+    #[test]
+    fn it_uses_first_match() {
+        let map = [(0..10, 100), (5..15, 200)];
+        assert_eq!(map_stage(&map, &[3..12, 20..25]), vec![20..25, 103..110, 210..212]);
+        assert_eq!(unmap_stage(&map, &[20..25, 103..110, 210..212]), vec![3..12, 20..25, 103..110, 210..212]);
+        assert_eq!(unmap_stage(&map, &[7..8, 207..208]), vec![207..208]);
+        assert_eq!(unmap_stage(&map, &[107..108, 212..213]), vec![7..8, 12..13, 107..108, 212..213]);
+    }
+
+    #[test]
+    fn it_maps_backwards() {
+        let almanac = Almanac::parse(TEST_INPUT);
+        for location in 0..120 {
+            let expected = (0..120).filter(|&seed| almanac.location(seed) == location).collect::<Vec<_>>();
+            let seeds = almanac.seeds_for_location(location);
+            assert_eq!(seeds.into_iter().filter(|seed| (0..120).contains(seed)).collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn it_traces_seeds() {
+        let almanac = Almanac::parse(TEST_INPUT);
+        let trace = almanac.trace(79);
+        let categories = trace.iter().map(|step| step.category).collect::<Vec<_>>();
+        assert_eq!(
+            categories,
+            vec!["seed", "soil", "fertilizer", "water", "light", "temperature", "humidity", "location"]
+        );
+        let values = trace.iter().map(|step| step.value).collect::<Vec<_>>();
+        assert_eq!(values, vec![79, 81, 81, 81, 74, 78, 78, 82]);
+        assert_eq!(trace[1].entry, Some(1));
+        assert_eq!(trace[2].entry, None);
+
+        let (seed, trace) = almanac.closest_seed(&almanac.seed_ranges()).unwrap();
+        assert_eq!(seed, 82);
+        assert_eq!(trace.last().unwrap().value, 46);
+    }
+
+    #[test]
+    fn it_checks_consistency() {
+        let almanac = Almanac::parse(TEST_INPUT);
+        assert_eq!(
+            almanac.check(),
+            vec![(0, Issue::DestinationOverlap { entries: (0, 1), range: 52..53 })]
+        );
+    }
+
+    #[test]
+    fn it_checks_identity_collisions() {
+        let almanac = Almanac::parse("seeds: 3 13\n\na-to-b map:\n0 10 5");
+        assert_eq!(almanac.location(3), almanac.location(13));
+        assert_eq!(almanac.check(), vec![(0, Issue::IdentityOverlap { entry: 0, range: 0..5 })]);
+        let almanac = Almanac::parse("seeds: 3 13\n\na-to-b map:\n0 5 5\n5 0 5");
+        assert_eq!(almanac.check(), vec![]);
+        let almanac = Almanac::parse("seeds: 3 13\n\na-to-b map:\n8 10 5\n10 5 2");
+        assert_eq!(
+            almanac.check(),
+            vec![
+                (0, Issue::DestinationOverlap { entries: (0, 1), range: 10..12 }),
+                (0, Issue::IdentityOverlap { entry: 0, range: 8..10 }),
+                (0, Issue::Gap { range: 7..10 }),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_resolves_overlaps() {
+        let almanac = Almanac::parse("seeds: 3 6\n\na-to-b map:\n100 0 10\n105 5 10\n50 30 5");
+        assert_eq!(
+            almanac.check(),
+            vec![
+                (0, Issue::SourceOverlap { entries: (0, 1), range: 5..10 }),
+                (0, Issue::DestinationOverlap { entries: (0, 1), range: 105..110 }),
+                (0, Issue::IdentityOverlap { entry: 0, range: 100..110 }),
+                (0, Issue::IdentityOverlap { entry: 1, range: 105..115 }),
+                (0, Issue::IdentityOverlap { entry: 2, range: 50..55 }),
+                (0, Issue::Gap { range: 15..30 }),
+            ]
+        );
+        assert_eq!(almanac.location(6), 106);
+        assert_eq!(almanac.location_ranges(&[3..9, 20..21]), vec![20..21, 103..109]);
+
+        let almanac = almanac.with_resolution(Resolution::LastWins).unwrap();
+        assert_eq!(almanac.location(6), 106);
+        assert_eq!(almanac.trace(6)[1].entry, Some(1));
+        assert_eq!(almanac.seeds_for_location(106), vec![6, 106]);
+
+        let almanac = Almanac::parse("seeds: 1 2\n\na-to-b map:\n10 0 5\n20 2 5");
+        assert_eq!(almanac.clone().with_resolution(Resolution::LastWins).unwrap().location(3), 21);
+        assert_eq!(almanac.location(3), 13);
+        assert_eq!(
+            almanac.stages()[0].lookup(3, Resolution::Error),
+            Err(Issue::SourceOverlap { entries: (0, 1), range: 2..5 })
+        );
+        assert_eq!(almanac.stages()[0].lookup(6, Resolution::Error), Ok((24, Some(1))));
+        assert!(matches!(
+            almanac.with_resolution(Resolution::Error),
+            Err((0, Issue::SourceOverlap { entries: (0, 1), .. }))
+        ));
+    }
+}
@@ -1,37 +1,7 @@
-fn combine_travel_ways(s: &str) -> usize {
-    let mut lines = s.lines();
-    let time: f64 = lines.next()
-        .unwrap()
-        .split_whitespace()
-        .skip(1)
-        .collect::<String>()
-        .parse()
-        .unwrap();
-    let distance: f64 = lines.last()
-        .unwrap()
-        .split_whitespace()
-        .skip(1)
-        .collect::<String>()
-        .parse()
-        .unwrap();
+use day_06::parse_single_race;
 
-        let opt_time_max = (time + (time * time - 4.0 * distance).sqrt()) / 2.0;
-        let opt_time_min = time - opt_time_max;
-        let mut opt_time_max_floor = opt_time_max.floor() as usize;
-        let mut opt_time_min_ceil = opt_time_min.ceil() as usize;
-        if opt_time_max_floor * opt_time_min_ceil == distance as usize {
-            opt_time_max_floor -= 1;
-            opt_time_min_ceil += 1;
-        }
-        opt_time_max_floor - opt_time_min_ceil + 1
-    
-    // T = a + b; a, r are Z; => a = T - b
-    // V = a; V_0 = 0;
-    // L = a * b; => L = (T - b) * b; => b ** 2 - b * T + L = 0;
-    // Discr = T * T - 4 * L
-    // x1, 2 = T +- sqrt(Discr) / 2
-    // optimal_b = (T + sqrt(T * T - 4 * L)) / 2;
-    // optimal a = T - b;
+fn combine_travel_ways(s: &str) -> u64 {
+    parse_single_race(s).ways_to_win()
 }
 
 fn main() {
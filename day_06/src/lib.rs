@@ -0,0 +1,276 @@
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Race {
+    pub time: u64,
+    pub distance: u64,
+}
+
+impl Race {
+    fn travelled(&self, hold: u128) -> u128 {
+        hold * (self.time as u128 - hold)
+    }
+
+    // T = a + b; travelled = (T - b) * b > L; => b ** 2 - b * T + L < 0;
+    // Discr = T * T - 4 * L; b in ((T - sqrt(Discr)) / 2, (T + sqrt(Discr)) / 2)
+    // isqrt only gives floor(sqrt(Discr)), so the lower bound is nudged until it is exact
+    // and the upper one follows by symmetry b <-> T - b.
+    pub fn ways_to_win(&self) -> u64 {
+        let (time, distance) = (self.time as u128, self.distance as u128);
+        let discr = match (time * time).checked_sub(4 * distance) {
+            Some(discr) if discr > 0 => discr,
+            _ => return 0,
+        };
+        let mut hold_min = (time - discr.isqrt().min(time)) / 2;
+        while hold_min <= time / 2 && self.travelled(hold_min) <= distance {
+            hold_min += 1;
+        }
+        while hold_min > 0 && self.travelled(hold_min - 1) > distance {
+            hold_min -= 1;
+        }
+        if hold_min > time / 2 {
+            return 0;
+        }
+        let hold_max = time - hold_min;
+        (hold_max - hold_min + 1) as u64
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Charge {
+    Linear { factor: u64 },
+    Quadratic { factor: u64 },
+    Capped { factor: u64, max_speed: u64 },
+}
+
+impl Charge {
+    // Saturates, which is harmless: any speed past DISTANCE_CAP already wins every race.
+    pub fn speed(&self, hold: u64) -> u128 {
+        let hold = hold as u128;
+        match *self {
+            Charge::Linear { factor } => factor as u128 * hold,
+            Charge::Quadratic { factor } => (factor as u128).saturating_mul(hold * hold),
+            Charge::Capped { factor, max_speed } => (factor as u128 * hold).min(max_speed as u128),
+        }
+    }
+}
+
+// Records are u64, so every distance beyond them is reported as this cap.
+pub const DISTANCE_CAP: u128 = u64::MAX as u128 + 1;
+
+// Drag slows the boat down by `drag` every millisecond until it stops.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Boat {
+    pub charge: Charge,
+    pub drag: u64,
+}
+
+impl Default for Boat {
+    fn default() -> Self {
+        Boat { charge: Charge::Linear { factor: 1 }, drag: 0 }
+    }
+}
+
+impl Boat {
+    // The boat covers speed, speed - drag, ... for `moving` milliseconds, an arithmetic
+    // series summing to moving * (first + last) / 2, capped at DISTANCE_CAP. The last
+    // leg is still positive, so the distance is at least `speed` once the boat moves.
+    pub fn travelled(&self, time: u64, hold: u64) -> u128 {
+        let speed = self.charge.speed(hold);
+        let remaining = time.saturating_sub(hold) as u128;
+        let drag = self.drag as u128;
+        let moving = if drag == 0 { remaining } else { remaining.min(speed.div_ceil(drag)) };
+        if moving == 0 {
+            return 0;
+        }
+        if speed >= DISTANCE_CAP {
+            return DISTANCE_CAP;
+        }
+        let last = speed - drag * (moving - 1);
+        (speed + last)
+            .checked_mul(moving)
+            .map_or(DISTANCE_CAP, |twice| (twice / 2).min(DISTANCE_CAP))
+    }
+
+    // Every charge curve gives a travelled distance that strictly rises up to its best
+    // hold and strictly falls after it, so the winning holds of a leg form one interval
+    // found by ternary search for the peak and binary search for both edges.
+    fn winning_holds_of(&self, race: &Race) -> Option<RangeInclusive<u64>> {
+        let travelled = |hold| self.travelled(race.time, hold);
+        let distance = race.distance as u128;
+        let (mut lo, mut hi) = (0, race.time);
+        while hi - lo > 2 {
+            let m1 = lo + (hi - lo) / 3;
+            let m2 = hi - (hi - lo) / 3;
+            if travelled(m1) < travelled(m2) {
+                lo = m1 + 1;
+            } else {
+                hi = m2;
+            }
+        }
+        let peak = (lo..=hi).max_by_key(|&hold| travelled(hold)).unwrap();
+        if travelled(peak) <= distance {
+            return None;
+        }
+        let (mut lo, mut hi) = (0, peak);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if travelled(mid) > distance { hi = mid } else { lo = mid + 1 }
+        }
+        let first = lo;
+        let (mut lo, mut hi) = (peak, race.time);
+        while lo < hi {
+            let mid = hi - (hi - lo) / 2;
+            if travelled(mid) > distance { lo = mid } else { hi = mid - 1 }
+        }
+        Some(first..=lo)
+    }
+
+    // Legs are raced with the same hold time, so a hold wins only if it wins every leg.
+    pub fn winning_holds(&self, legs: &[Race]) -> Vec<RangeInclusive<u64>> {
+        let mut res = 0..=u64::MAX;
+        for leg in legs {
+            match self.winning_holds_of(leg) {
+                Some(holds) => res = *res.start().max(holds.start())..=*res.end().min(holds.end()),
+                None => return vec![],
+            }
+        }
+        if legs.is_empty() || res.is_empty() { vec![] } else { vec![res] }
+    }
+}
+
+fn parse_line(line: &str) -> impl Iterator<Item = &str> {
+    line.split_whitespace().skip(1)
+}
+
+pub fn parse_races(s: &str) -> Vec<Race> {
+    let mut lines = s.lines();
+    let times = parse_line(lines.next().unwrap());
+    let distances = parse_line(lines.next().unwrap());
+    times
+        .zip(distances)
+        .map(|(time, distance)| Race {
+            time: time.parse().unwrap(),
+            distance: distance.parse().unwrap(),
+        })
+        .collect()
+}
+
+pub fn parse_single_race(s: &str) -> Race {
+    let mut lines = s.lines();
+    let time = parse_line(lines.next().unwrap()).collect::<String>();
+    let distance = parse_line(lines.next().unwrap()).collect::<String>();
+    Race {
+        time: time.parse().unwrap(),
+        distance: distance.parse().unwrap(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force(race: &Race) -> u64 {
+        (0..=race.time as u128)
+            .filter(|&hold| race.travelled(hold) > race.distance as u128)
+            .count() as u64
+    }
+
+    #[test]
+    fn it_matches_brute_force() {
+        for time in 0..80 {
+            for distance in 0..=(time * time / 4 + 2) {
+                let race = Race { time, distance };
+                assert_eq!(race.ways_to_win(), brute_force(&race), "{race:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn it_is_exact_for_huge_races() {
+        let time = u64::MAX;
+        let race = Race { time, distance: 0 };
+        assert_eq!(race.ways_to_win(), u64::MAX - 1);
+        assert_eq!(Race { time, distance: u64::MAX }.ways_to_win(), u64::MAX - 3);
+        let time = (1 << 31) + 1;
+        let best = (time / 2) * (time / 2 + 1);
+        assert_eq!(Race { time, distance: best }.ways_to_win(), 0);
+        assert_eq!(Race { time, distance: best - 1 }.ways_to_win(), 2);
+        let time = 1 << 32;
+        let best = (time / 2) * (time / 2);
+        assert_eq!(Race { time, distance: best - 1 }.ways_to_win(), 1);
+        assert_eq!(Race { time, distance: best - 4 }.ways_to_win(), 3);
+    }
+
+    #[test]
+    fn it_solves_boat_physics() {
+        let boats = [
+            Boat::default(),
+            Boat { charge: Charge::Linear { factor: 3 }, drag: 0 },
+            Boat { charge: Charge::Linear { factor: 2 }, drag: 1 },
+            Boat { charge: Charge::Quadratic { factor: 1 }, drag: 0 },
+            Boat { charge: Charge::Quadratic { factor: 2 }, drag: 5 },
+            Boat { charge: Charge::Capped { factor: 2, max_speed: 7 }, drag: 0 },
+            Boat { charge: Charge::Capped { factor: 1, max_speed: 9 }, drag: 2 },
+            Boat { charge: Charge::Linear { factor: 1 }, drag: 40 },
+        ];
+        for boat in boats {
+            for time in 0..30 {
+                let best = (0..=time).map(|hold| boat.travelled(time, hold)).max().unwrap() as u64;
+                for distance in 0..=best + 1 {
+                    let race = Race { time, distance };
+                    let expected = (0..=time)
+                        .filter(|&hold| boat.travelled(time, hold) > distance as u128)
+                        .collect::<Vec<_>>();
+                    let holds = boat.winning_holds(&[race]);
+                    let holds = holds.into_iter().flatten().collect::<Vec<_>>();
+                    assert_eq!(holds, expected, "{boat:?} {race:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_handles_huge_boats() {
+        let boat = Boat { charge: Charge::Quadratic { factor: 1 }, drag: 2 };
+        assert_eq!(boat.travelled(u64::MAX, 1 << 33), DISTANCE_CAP);
+        assert_eq!(boat.travelled(u64::MAX, u64::MAX), 0);
+        assert_eq!(boat.travelled(u64::MAX, 3), 9 + 7 + 5 + 3 + 1);
+        let boat = Boat { charge: Charge::Linear { factor: 1 }, drag: 1 };
+        assert_eq!(boat.travelled(1 << 33, 1 << 31), (1 << 31) * ((1 << 31) + 1) / 2);
+        let boat = Boat::default();
+        assert_eq!(boat.travelled(u64::MAX, 1), (u64::MAX - 1) as u128);
+        assert_eq!(boat.travelled(u64::MAX, u64::MAX / 2), DISTANCE_CAP);
+
+        let race = Race { time: u64::MAX, distance: u64::MAX };
+        for boat in [
+            Boat::default(),
+            Boat { charge: Charge::Quadratic { factor: 1 }, drag: 2 },
+            Boat { charge: Charge::Quadratic { factor: u64::MAX }, drag: u64::MAX },
+            Boat { charge: Charge::Capped { factor: 3, max_speed: u64::MAX }, drag: 1 << 20 },
+        ] {
+            let holds = boat.winning_holds(&[race]);
+            let [holds] = holds.as_slice() else { panic!("{boat:?}") };
+            let (first, last) = (*holds.start(), *holds.end());
+            let distance = race.distance as u128;
+            assert!(boat.travelled(race.time, first) > distance, "{boat:?}");
+            assert!(boat.travelled(race.time, first - 1) <= distance, "{boat:?}");
+            assert!(boat.travelled(race.time, last) > distance, "{boat:?}");
+            assert!(boat.travelled(race.time, last + 1) <= distance, "{boat:?}");
+        }
+        assert_eq!(Boat::default().winning_holds(&[race]), vec![2..=u64::MAX - 2]);
+    }
+
+    #[test]
+    fn it_solves_stock_and_multi_leg_races() {
+        let boat = Boat::default();
+        for race in parse_races("Time:      7  15   30\nDistance:  9  40  200") {
+            let ways = boat.winning_holds(&[race]).into_iter().map(|holds| holds.count() as u64).sum::<u64>();
+            assert_eq!(ways, race.ways_to_win());
+        }
+        let legs = [Race { time: 7, distance: 9 }, Race { time: 8, distance: 12 }];
+        assert_eq!(boat.winning_holds(&legs), vec![3..=5]);
+        let legs = [Race { time: 7, distance: 9 }, Race { time: 30, distance: 200 }];
+        assert_eq!(boat.winning_holds(&legs), vec![]);
+    }
+}
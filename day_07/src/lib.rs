@@ -0,0 +1,354 @@
+use std::cmp::{Ordering, Reverse};
+use std::collections::HashMap;
+use std::fmt;
+use nom::IResult;
+use nom::character::complete::{alphanumeric1, u32 as d};
+use nom::sequence::tuple;
+use nom_supreme::{tag::complete::tag, ParserExt};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HandError {
+    Parse(String),
+    UnknownCard(char),
+    WrongSize(usize),
+    NoCategory(Vec<usize>),
+}
+
+impl fmt::Display for HandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HandError::Parse(line) => write!(f, "can't parse hand: {line:?}"),
+            HandError::UnknownCard(card) => write!(f, "unknown card {card:?}"),
+            HandError::WrongSize(size) => write!(f, "hand has {size} cards"),
+            HandError::NoCategory(signature) => write!(f, "no category for signature {signature:?}"),
+        }
+    }
+}
+
+impl std::error::Error for HandError {}
+
+fn string_to_hand(input: &str) -> IResult<&str, (&str, u32)> {
+    tuple((alphanumeric1, d.preceded_by(tag(" "))))(input)
+}
+
+fn partitions(n: usize, max_part: usize) -> Vec<Vec<usize>> {
+    if n == 0 {
+        return vec![vec![]];
+    }
+    (1..=n.min(max_part))
+        .rev()
+        .flat_map(|part| {
+            partitions(n - part, part).into_iter().map(move |mut rest| {
+                rest.insert(0, part);
+                rest
+            })
+        })
+        .collect()
+}
+
+// Count signatures sorted from the biggest group down compare lexicographically in
+// the usual poker order: high card < one pair < two pair < three of a kind < ...
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    pub card_order: Vec<char>,
+    pub wild: Vec<char>,
+    pub hand_size: usize,
+    pub categories: Vec<Vec<usize>>,
+}
+
+impl Rules {
+    pub fn new(card_order: &str, wild: &str, hand_size: usize) -> Self {
+        let mut categories = partitions(hand_size, hand_size);
+        categories.sort();
+        Rules {
+            card_order: card_order.chars().collect(),
+            wild: wild.chars().collect(),
+            hand_size,
+            categories,
+        }
+    }
+
+    pub fn standard() -> Self {
+        Rules::new("23456789TJQKA", "", 5)
+    }
+
+    pub fn jokers() -> Self {
+        Rules::new("J23456789TQKA", "J", 5)
+    }
+
+    pub fn category_name(&self, category: usize) -> String {
+        let signature = &self.categories[category];
+        let name = match signature.as_slice() {
+            [1, 1, 1, 1, 1] => "High card",
+            [2, 1, 1, 1] => "One pair",
+            [2, 2, 1] => "Two pair",
+            [3, 1, 1] => "Three of a kind",
+            [3, 2] => "Full house",
+            [4, 1] => "Four of a kind",
+            [5] => "Five of a kind",
+            _ => return signature.iter().map(|count| count.to_string()).collect::<Vec<_>>().join("+"),
+        };
+        name.to_string()
+    }
+
+    fn strength(&self, card: char) -> Result<usize, HandError> {
+        self.card_order
+            .iter()
+            .position(|&c| c == card)
+            .ok_or(HandError::UnknownCard(card))
+    }
+
+    pub fn hand(&self, cards: &str) -> Result<Hand, HandError> {
+        let cards: Vec<char> = cards.chars().collect();
+        if cards.len() != self.hand_size {
+            return Err(HandError::WrongSize(cards.len()));
+        }
+        let strengths = cards
+            .iter()
+            .map(|&card| self.strength(card))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut card_counter: HashMap<char, usize> = HashMap::new();
+        for card in cards.iter().filter(|card| !self.wild.contains(card)) {
+            *card_counter.entry(*card).or_insert(0) += 1;
+        }
+        let mut groups: Vec<(char, usize)> = card_counter.into_iter().collect();
+        groups.sort_by_key(|&(card, count)| Reverse((count, self.strength(card).unwrap())));
+        let signature: Vec<usize> = groups.iter().map(|&(_, count)| count).collect();
+
+        // Wildcards can fill any group up, so a category is reachable when every group
+        // fits into its slot once both are sorted from the biggest down.
+        let reachable = |category: &Vec<usize>| {
+            signature.len() <= category.len()
+                && signature.iter().zip(category.iter()).all(|(count, slot)| count <= slot)
+        };
+        let category = self
+            .categories
+            .iter()
+            .rposition(reachable)
+            .ok_or_else(|| HandError::NoCategory(signature.clone()))?;
+
+        let mut spare = self
+            .card_order
+            .iter()
+            .rev()
+            .filter(|card| !self.wild.contains(card) && !groups.iter().any(|(c, _)| c == *card));
+        let mut fills = vec![];
+        for (i, &slot) in self.categories[category].iter().enumerate() {
+            let (card, count) = match groups.get(i) {
+                Some(&(card, count)) => (card, count),
+                None => (*spare.next().unwrap_or_else(|| &self.wild[0]), 0),
+            };
+            fills.extend(std::iter::repeat_n(card, slot - count));
+        }
+        let mut fills = fills.into_iter();
+        let played_as = cards
+            .iter()
+            .map(|card| if self.wild.contains(card) { fills.next().unwrap() } else { *card })
+            .collect();
+
+        Ok(Hand {
+            cards,
+            played_as,
+            category,
+            strengths,
+        })
+    }
+
+    pub fn parse_line(&self, input: &str) -> Result<(Hand, u32), HandError> {
+        let (_, (cards, bet)) = string_to_hand(input).map_err(|_| HandError::Parse(input.to_string()))?;
+        Ok((self.hand(cards)?, bet))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hand {
+    pub cards: Vec<char>,
+    pub played_as: Vec<char>,
+    pub category: usize,
+    pub strengths: Vec<usize>,
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.category
+            .cmp(&other.category)
+            .then_with(|| self.strengths.cmp(&other.strengths))
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// How a hand was ordered against a neighbour in the standing table: by category,
+// by the first card position that differs, or not at all for identical hands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decider {
+    Category,
+    Card(usize),
+    Tie,
+}
+
+impl Decider {
+    fn between(a: &Hand, b: &Hand) -> Self {
+        if a.category != b.category {
+            return Decider::Category;
+        }
+        match a.strengths.iter().zip(b.strengths.iter()).position(|(x, y)| x != y) {
+            Some(position) => Decider::Card(position),
+            None => Decider::Tie,
+        }
+    }
+}
+
+impl fmt::Display for Decider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Decider::Category => write!(f, "category"),
+            Decider::Card(position) => write!(f, "card {}", position + 1),
+            Decider::Tie => write!(f, "tie"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Standing {
+    pub rank: usize,
+    pub hand: Hand,
+    pub bet: u32,
+    pub category_name: String,
+    pub below: Option<Decider>,
+    pub above: Option<Decider>,
+}
+
+impl fmt::Display for Standing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cards = self.hand.cards.iter().collect::<String>();
+        let played_as = self.hand.played_as.iter().collect::<String>();
+        let neighbour = |decider: Option<Decider>| decider.map_or("-".to_string(), |decider| decider.to_string());
+        write!(
+            f,
+            "{:>5} {cards} {:>5} {:<16} as {played_as}  below: {:<8} above: {}",
+            self.rank,
+            self.bet,
+            self.category_name,
+            neighbour(self.below),
+            neighbour(self.above),
+        )
+    }
+}
+
+pub fn standings(rules: &Rules, s: &str) -> Result<Vec<Standing>, HandError> {
+    let mut hands = s
+        .lines()
+        .map(|line| rules.parse_line(line))
+        .collect::<Result<Vec<_>, _>>()?;
+    hands.sort();
+    Ok((0..hands.len())
+        .map(|i| {
+            let (hand, bet) = &hands[i];
+            Standing {
+                rank: i + 1,
+                hand: hand.clone(),
+                bet: *bet,
+                category_name: rules.category_name(hand.category),
+                below: i.checked_sub(1).map(|j| Decider::between(hand, &hands[j].0)),
+                above: hands.get(i + 1).map(|(other, _)| Decider::between(hand, other)),
+            }
+        })
+        .collect())
+}
+
+pub fn standing_table(rules: &Rules, s: &str) -> Result<String, HandError> {
+    Ok(standings(rules, s)?
+        .iter()
+        .map(|standing| format!("{standing}\n"))
+        .collect())
+}
+
+pub fn play_poker_game(rules: &Rules, s: &str) -> Result<u64, HandError> {
+    Ok(standings(rules, s)?
+        .iter()
+        .map(|standing| standing.rank as u64 * standing.bet as u64)
+        .sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str = "32T3K 765
+T55J5 684
+KK677 28
+KTJJT 220
+QQQJA 483";
+
+    #[test]
+    fn it_plays_both_rule_sets() {
+        assert_eq!(play_poker_game(&Rules::standard(), TEST_INPUT), Ok(6440));
+        assert_eq!(play_poker_game(&Rules::jokers(), TEST_INPUT), Ok(5905));
+    }
+
+    #[test]
+    fn it_classifies_hands() {
+        let rules = Rules::jokers();
+        assert_eq!(rules.categories.len(), 7);
+        let category = |cards| rules.categories[rules.hand(cards).unwrap().category].clone();
+        assert_eq!(category("23456"), vec![1, 1, 1, 1, 1]);
+        assert_eq!(category("2345J"), vec![2, 1, 1, 1]);
+        assert_eq!(category("2233J"), vec![3, 2]);
+        assert_eq!(category("223JJ"), vec![4, 1]);
+        assert_eq!(category("JJJJJ"), vec![5]);
+        assert_eq!(rules.hand("KTJJT").unwrap().played_as, vec!['K', 'T', 'T', 'T', 'T']);
+        assert_eq!(rules.hand("JJJJJ").unwrap().played_as, vec!['A'; 5]);
+        assert_eq!(rules.hand("2345X"), Err(HandError::UnknownCard('X')));
+        assert_eq!(rules.hand("2345"), Err(HandError::WrongSize(4)));
+    }
+
+    #[test]
+    fn it_supports_variants() {
+        let rules = Rules::new("J23456789TQKA*", "J*", 6);
+        assert_eq!(rules.categories.len(), 11);
+        let hand = rules.hand("2233J*").unwrap();
+        assert_eq!(rules.categories[hand.category], vec![4, 2]);
+        assert_eq!(hand.played_as, vec!['2', '2', '3', '3', '3', '3']);
+        assert!(rules.hand("22334J").unwrap() < rules.hand("22233J").unwrap());
+
+        let rules = Rules {
+            categories: vec![vec![1, 1, 1, 1, 1], vec![5]],
+            ..Rules::standard()
+        };
+        assert_eq!(rules.hand("22223"), Err(HandError::NoCategory(vec![4, 1])));
+    }
+
+    #[test]
+    fn it_explains_standings() {
+        let rules = Rules::jokers();
+        let standings = standings(&rules, TEST_INPUT).unwrap();
+        let summary = standings
+            .iter()
+            .map(|standing| (standing.hand.cards.iter().collect::<String>(), standing.below, standing.above))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                ("32T3K".to_string(), None, Some(Decider::Category)),
+                ("KK677".to_string(), Some(Decider::Category), Some(Decider::Category)),
+                ("T55J5".to_string(), Some(Decider::Category), Some(Decider::Card(0))),
+                ("QQQJA".to_string(), Some(Decider::Card(0)), Some(Decider::Card(0))),
+                ("KTJJT".to_string(), Some(Decider::Card(0)), None),
+            ]
+        );
+        assert_eq!(standings[4].category_name, "Four of a kind");
+        assert_eq!(
+            standings[4].to_string(),
+            "    5 KTJJT   220 Four of a kind   as KTTTT  below: card 1   above: -"
+        );
+        let table = standing_table(&rules, "AAAA2 1\nAAAA2 2\nAAAA3 3").unwrap();
+        assert!(table.lines().next().unwrap().ends_with("below: -        above: tie"));
+        assert!(table.lines().nth(2).unwrap().contains("below: card 5"));
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use num::Integer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstructionError {
+    Empty,
+    Invalid { position: usize, char: char },
+}
+
+impl fmt::Display for InstructionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstructionError::Empty => write!(f, "instructions must not be empty"),
+            InstructionError::Invalid { position, char } => {
+                write!(f, "invalid instruction {char:?} at position {position}, expected L or R")
+            }
+        }
+    }
+}
+
+impl std::error::Error for InstructionError {}
+
+fn parse_instructions(path: &str) -> Result<Vec<usize>, InstructionError> {
+    if path.is_empty() {
+        return Err(InstructionError::Empty);
+    }
+    path.chars()
+        .enumerate()
+        .map(|(position, char)| match char {
+            'L' => Ok(0_usize),
+            'R' => Ok(1_usize),
+            _ => Err(InstructionError::Invalid { position, char }),
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct Network<'a> {
+    instructions: Vec<usize>,
+    names: Vec<&'a str>,
+    ids: HashMap<&'a str, usize>,
+    ways: Vec<[usize; 2]>,
+}
+
+impl<'a> Network<'a> {
+    pub fn parse(s: &'a str) -> Self {
+        let mut lines = s.lines().filter(|line| !line.is_empty());
+        let instructions = parse_instructions(lines.next().unwrap()).unwrap();
+        let way_map: Vec<(&str, [&str; 2])> = lines.map(|line| {
+            let mut parts = line.split(" = ");
+            let destination = parts.next().unwrap();
+            let ways = parts.last().unwrap();
+            let trimmed_ways: Vec<&str> = ways[1..ways.len() - 1].split(", ").collect();
+            let [first_way, last_way] = &trimmed_ways[0..2] else { unreachable!() };
+            (destination, [*first_way, *last_way])
+        }).collect();
+
+        let names: Vec<&str> = way_map.iter().map(|(name, _)| *name).collect();
+        let ids: HashMap<&str, usize> = names.iter().enumerate().map(|(id, name)| (*name, id)).collect();
+        let ways = way_map
+            .iter()
+            .map(|(_, [left, right])| [ids[left], ids[right]])
+            .collect();
+        Network { instructions, names, ids, ways }
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &'a str {
+        self.names[id]
+    }
+
+    pub fn nodes_where(&self, predicate: impl Fn(&str) -> bool) -> Vec<usize> {
+        (0..self.names.len()).filter(|&id| predicate(self.names[id])).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    // Left is 0 and right is 1.
+    pub fn instructions(&self) -> &[usize] {
+        &self.instructions
+    }
+
+    pub fn with_instructions(&self, path: &str) -> Result<Self, InstructionError> {
+        Ok(Network { instructions: parse_instructions(path)?, ..self.clone() })
+    }
+
+    pub fn next(&self, node: usize, step: usize) -> usize {
+        self.ways[node][self.instructions[step % self.instructions.len()]]
+    }
+
+    pub fn walk(&self, start: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut node = start;
+        (0..).map(move |step| {
+            let cur = node;
+            node = self.next(node, step);
+            cur
+        })
+    }
+
+    pub fn steps_to(&self, start: usize, is_target: impl Fn(&str) -> bool) -> Option<usize> {
+        let cycle = self.ghost_cycle(start, is_target);
+        cycle.pre_cycle_hits.into_iter().chain(cycle.cycle_hits).next()
+    }
+
+    pub fn visited(&self, start: usize) -> HashSet<usize> {
+        let cycle = self.ghost_cycle(start, |_| false);
+        self.walk(start).take(cycle.offset + cycle.length).collect()
+    }
+
+    pub fn loop_nodes(&self, start: usize) -> Vec<usize> {
+        let cycle = self.ghost_cycle(start, |_| false);
+        self.walk(start).skip(cycle.offset).take(cycle.length).collect()
+    }
+
+    pub fn reachable(&self, starts: &[usize]) -> HashSet<usize> {
+        let mut seen: HashSet<usize> = starts.iter().copied().collect();
+        let mut stack = starts.to_vec();
+        while let Some(node) = stack.pop() {
+            for next in self.ways[node] {
+                if seen.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        seen
+    }
+
+    pub fn unreachable(&self, starts: &[usize]) -> Vec<usize> {
+        let reachable = self.reachable(starts);
+        (0..self.len()).filter(|node| !reachable.contains(node)).collect()
+    }
+
+    // Walks (node, instruction index) states until one repeats; from then on the walk
+    // loops forever, so every target hit is either one-off or repeats every `length` steps.
+    pub fn ghost_cycle(&self, start: usize, is_target: impl Fn(&str) -> bool) -> GhostCycle {
+        let path_len = self.instructions.len();
+        let mut seen: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut hits = vec![];
+        let mut node = start;
+        let mut steps = 0;
+        let offset = loop {
+            if let Some(&first) = seen.get(&(node, steps % path_len)) {
+                break first;
+            }
+            seen.insert((node, steps % path_len), steps);
+            if is_target(self.names[node]) {
+                hits.push(steps);
+            }
+            node = self.next(node, steps);
+            steps += 1;
+        };
+        let (pre_cycle_hits, cycle_hits) = hits.into_iter().partition(|&hit| hit < offset);
+        GhostCycle {
+            offset,
+            length: steps - offset,
+            pre_cycle_hits,
+            cycle_hits,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GhostCycle {
+    pub offset: usize,
+    pub length: usize,
+    pub pre_cycle_hits: Vec<usize>,
+    pub cycle_hits: Vec<usize>,
+}
+
+impl GhostCycle {
+    pub fn hits_at(&self, steps: usize) -> bool {
+        if steps < self.offset {
+            self.pre_cycle_hits.contains(&steps)
+        } else {
+            let phase = self.offset + (steps - self.offset) % self.length;
+            self.cycle_hits.contains(&phase)
+        }
+    }
+}
+
+fn combine_congruences((a1, m1): (i128, i128), (a2, m2): (i128, i128)) -> Option<(i128, i128)> {
+    let egcd = m1.extended_gcd(&m2);
+    let gcd = egcd.gcd;
+    if (a2 - a1) % gcd != 0 {
+        return None;
+    }
+    let lcm = m1 / gcd * m2;
+    let k = ((a2 - a1) / gcd * egcd.x).rem_euclid(m2 / gcd);
+    Some(((a1 + m1 * k).rem_euclid(lcm), lcm))
+}
+
+// Earliest step at which every ghost stands on a target at once, or None when the
+// hit sets never intersect. Steps before the last ghost enters its cycle are checked
+// one by one, later ones by the generalised CRT over the cycle hit residues.
+pub fn first_simultaneous_arrival(cycles: &[GhostCycle]) -> Option<usize> {
+    let latest = cycles.iter().max_by_key(|cycle| cycle.offset)?;
+    let early = latest
+        .pre_cycle_hits
+        .iter()
+        .copied()
+        .find(|&steps| cycles.iter().all(|cycle| cycle.hits_at(steps)));
+    if early.is_some() {
+        return early;
+    }
+
+    let mut congruences = vec![(0_i128, 1_i128)];
+    for cycle in cycles {
+        let length = cycle.length as i128;
+        congruences = congruences
+            .iter()
+            .flat_map(|&congruence| {
+                cycle
+                    .cycle_hits
+                    .iter()
+                    .filter_map(move |&hit| combine_congruences(congruence, (hit as i128 % length, length)))
+            })
+            .collect();
+        congruences.sort();
+        congruences.dedup();
+    }
+    let start = latest.offset as i128;
+    congruences
+        .into_iter()
+        .map(|(residue, modulus)| start + (residue - start).rem_euclid(modulus))
+        .min()
+        .map(|steps| steps as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_decomposes_cycles() {
+        let network = Network::parse(
+"LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)");
+        let cycle = network.ghost_cycle(network.id("22A").unwrap(), |name| name.ends_with('Z'));
+        assert_eq!(cycle, GhostCycle { offset: 1, length: 6, pre_cycle_hits: vec![], cycle_hits: vec![3, 6] });
+        assert!(cycle.hits_at(9));
+        assert!(!cycle.hits_at(10));
+    }
+
+    #[test]
+    fn it_combines_congruences() {
+        assert_eq!(combine_congruences((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(combine_congruences((1, 4), (3, 6)), Some((9, 12)));
+        assert_eq!(combine_congruences((1, 4), (2, 6)), None);
+    }
+
+    #[test]
+    fn it_finds_arrival_without_lcm_assumption() {
+        let cycle = |offset, length, pre_cycle_hits: &[usize], cycle_hits: &[usize]| GhostCycle {
+            offset,
+            length,
+            pre_cycle_hits: pre_cycle_hits.to_vec(),
+            cycle_hits: cycle_hits.to_vec(),
+        };
+        let ghosts = [cycle(2, 4, &[], &[3]), cycle(0, 6, &[], &[1])];
+        assert_eq!(first_simultaneous_arrival(&ghosts), Some(7));
+        let ghosts = [cycle(3, 4, &[1], &[5]), cycle(0, 3, &[], &[1])];
+        assert_eq!(first_simultaneous_arrival(&ghosts), Some(1));
+        let ghosts = [cycle(0, 4, &[], &[1]), cycle(0, 6, &[], &[2])];
+        assert_eq!(first_simultaneous_arrival(&ghosts), None);
+        let ghosts = [cycle(0, 4, &[], &[1, 2]), cycle(0, 6, &[], &[2])];
+        assert_eq!(first_simultaneous_arrival(&ghosts), Some(2));
+    }
+
+    #[test]
+    fn it_matches_brute_force() {
+        let network = Network::parse(
+"LRR
+
+A1 = (B1, C1)
+B1 = (Z1, C1)
+C1 = (A1, Z1)
+Z1 = (C1, B1)
+A2 = (Z2, B2)
+B2 = (Z2, A2)
+Z2 = (B2, B2)");
+        let starts = network.nodes_where(|name| name.starts_with('A'));
+        let is_target = |name: &str| name.starts_with('Z');
+        let cycles = starts.iter().map(|&start| network.ghost_cycle(start, is_target)).collect::<Vec<_>>();
+        let mut nodes = starts.clone();
+        let mut steps = 0;
+        while !nodes.iter().all(|&node| is_target(network.name(node))) {
+            nodes = nodes.iter().map(|&node| network.next(node, steps)).collect();
+            steps += 1;
+        }
+        assert_eq!(first_simultaneous_arrival(&cycles), Some(steps));
+    }
+
+    #[test]
+    fn it_queries_network() {
+        let network = Network::parse(
+"LLR
+
+AAA = (BBB, BBB)
+BBB = (AAA, ZZZ)
+ZZZ = (ZZZ, ZZZ)
+QQQ = (AAA, QQQ)");
+        let id = |name| network.id(name).unwrap();
+        assert_eq!(network.len(), 4);
+        assert_eq!(network.steps_to(id("AAA"), |name| name == "ZZZ"), Some(6));
+        assert_eq!(network.steps_to(id("ZZZ"), |name| name == "AAA"), None);
+        let rights = network.with_instructions("R").unwrap();
+        assert_eq!(rights.instructions(), &[1]);
+        assert_eq!(network.with_instructions("").unwrap_err(), InstructionError::Empty);
+        assert_eq!(
+            network.with_instructions("LRX").unwrap_err(),
+            InstructionError::Invalid { position: 2, char: 'X' }
+        );
+        assert_eq!(rights.steps_to(id("AAA"), |name| name == "ZZZ"), Some(2));
+        assert_eq!(rights.steps_to(id("QQQ"), |name| name == "ZZZ"), None);
+
+        assert_eq!(network.visited(id("AAA")), HashSet::from([id("AAA"), id("BBB"), id("ZZZ")]));
+        assert_eq!(network.loop_nodes(id("AAA")), vec![id("ZZZ"); 3]);
+        assert_eq!(rights.loop_nodes(id("QQQ")), vec![id("QQQ")]);
+        assert_eq!(network.unreachable(&[id("AAA")]), vec![id("QQQ")]);
+        assert!(network.unreachable(&[id("QQQ")]).is_empty());
+        assert_eq!(
+            network.walk(id("AAA")).take(4).map(|node| network.name(node)).collect::<Vec<_>>(),
+            vec!["AAA", "BBB", "AAA", "BBB"]
+        );
+    }
+}
@@ -1,32 +1,12 @@
-use itertools::Itertools;
+use day_09::{parse_history, Polynomial};
+use num::ToPrimitive;
 
-
-fn extrapolate_line(line: &str) -> i32 {
-    let ints = line.split_whitespace()
-        .map(|num| num.parse::<i32>().unwrap())
-        .collect::<Vec<i32>>();
-    let mut extrapolations = vec![ints];
-    while !extrapolations.last().unwrap().iter().all(|num| *num == 0) {
-        let iteration = extrapolations.last()
-            .unwrap()
-            .iter()
-            .tuple_windows()
-            .map(|(prev, next)| next - prev)
-            .collect::<Vec<i32>>();
-        extrapolations.push(iteration);
-    }
-
-    let extr_len = extrapolations.len();
-    for idx in (0..extr_len - 1).rev() {
-        let former_last = *extrapolations[idx + 1].last().unwrap();
-        let latter = &mut extrapolations[idx];
-        latter.push(former_last + *latter.last().unwrap());
-    }
-
-    *extrapolations[0].last().unwrap()
+fn extrapolate_line(line: &str) -> i64 {
+    let history = parse_history(line);
+    Polynomial::fit(&history).unwrap().at(history.len()).to_i64().unwrap()
 }
 
-fn extrapolate(s: &str) -> i32 {
+fn extrapolate(s: &str) -> i64 {
     s.lines()
         .map(extrapolate_line)
         .sum()
@@ -1,33 +1,12 @@
-use itertools::Itertools;
-use std::collections::VecDeque;
+use day_09::{parse_history, Polynomial};
+use num::ToPrimitive;
 
-
-fn extrapolate_line(line: &str) -> i32 {
-    let ints = line.split_whitespace()
-        .map(|num| num.parse::<i32>().unwrap())
-        .collect::<VecDeque<i32>>();
-    let mut extrapolations = vec![ints];
-    while !extrapolations.last().unwrap().iter().all(|num| *num == 0) {
-        let iteration = extrapolations.last()
-            .unwrap()
-            .iter()
-            .tuple_windows()
-            .map(|(prev, next)| next - prev)
-            .collect::<VecDeque<i32>>();
-        extrapolations.push(iteration);
-    }
-
-    let extr_len = extrapolations.len();
-    for idx in (0..extr_len - 1).rev() {
-        let former_first = extrapolations[idx + 1][0];
-        let latter = &mut extrapolations[idx];
-        latter.push_front(latter[0] - former_first);
-    }
-
-    extrapolations[0][0]
+fn extrapolate_line(line: &str) -> i64 {
+    let history = parse_history(line);
+    Polynomial::fit(&history).unwrap().at(-1).to_i64().unwrap()
 }
 
-fn extrapolate(s: &str) -> i32 {
+fn extrapolate(s: &str) -> i64 {
     s.lines()
         .map(extrapolate_line)
         .sum()
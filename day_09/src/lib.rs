@@ -0,0 +1,230 @@
+use itertools::Itertools;
+use num::{BigInt, BigRational, Zero};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotPolynomial {
+    pub len: usize,
+}
+
+impl fmt::Display for NotPolynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "differences of {} values never reach zero", self.len)
+    }
+}
+
+impl std::error::Error for NotPolynomial {}
+
+// Newton's forward form: f(n) = sum over k of C(n, k) * differences[k], where
+// differences[k] is the first value of the k-th difference row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    pub degree: usize,
+    pub evidence: usize,
+    differences: Vec<BigInt>,
+}
+
+impl Polynomial {
+    pub fn fit(values: &[BigInt]) -> Result<Self, NotPolynomial> {
+        let not_polynomial = NotPolynomial { len: values.len() };
+        let mut row = values.to_vec();
+        let mut differences = vec![];
+        while !row.iter().all(Zero::is_zero) {
+            differences.push(row[0].clone());
+            row = row
+                .iter()
+                .tuple_windows()
+                .map(|(prev, next)| next - prev)
+                .collect();
+        }
+        if row.is_empty() {
+            return Err(not_polynomial);
+        }
+        Ok(Polynomial {
+            degree: differences.len().saturating_sub(1),
+            evidence: row.len(),
+            differences,
+        })
+    }
+
+    pub fn at(&self, index: impl Into<BigInt>) -> BigInt {
+        let index = index.into();
+        let mut binomial = BigInt::from(1);
+        let mut res = BigInt::zero();
+        for (k, difference) in self.differences.iter().enumerate() {
+            if k > 0 {
+                binomial = binomial * (&index - (k - 1)) / k;
+            }
+            res += &binomial * difference;
+        }
+        res
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Geometric {
+    pub first: BigRational,
+    pub ratio: BigRational,
+}
+
+impl Geometric {
+    pub fn fit(values: &[BigInt]) -> Option<Self> {
+        if values.len() < 3 || values.iter().any(Zero::is_zero) {
+            return None;
+        }
+        let ratio = BigRational::new(values[1].clone(), values[0].clone());
+        let is_geometric = values
+            .iter()
+            .tuple_windows()
+            .all(|(prev, next)| BigRational::new(next.clone(), prev.clone()) == ratio);
+        is_geometric.then(|| Geometric { first: BigRational::from(values[0].clone()), ratio })
+    }
+
+    pub fn at(&self, index: i32) -> BigRational {
+        &self.first * self.ratio.pow(index)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum History {
+    Polynomial(Polynomial),
+    Geometric(Geometric),
+    Unknown,
+}
+
+impl History {
+    pub fn classify(values: &[BigInt]) -> Self {
+        if let Ok(polynomial) = Polynomial::fit(values) {
+            History::Polynomial(polynomial)
+        } else if let Some(geometric) = Geometric::fit(values) {
+            History::Geometric(geometric)
+        } else {
+            History::Unknown
+        }
+    }
+
+    pub fn at(&self, index: i32) -> Option<BigRational> {
+        match self {
+            History::Polynomial(polynomial) => Some(BigRational::from(polynomial.at(index))),
+            History::Geometric(geometric) => Some(geometric.at(index)),
+            History::Unknown => None,
+        }
+    }
+}
+
+impl fmt::Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            History::Polynomial(polynomial) => write!(f, "polynomial of degree {}", polynomial.degree),
+            History::Geometric(geometric) => write!(f, "geometric with ratio {}", geometric.ratio),
+            History::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub line: usize,
+    pub history: History,
+    pub justified: bool,
+    pub next: Option<BigRational>,
+    pub previous: Option<BigRational>,
+}
+
+// A polynomial fit is only trusted when at least `min_evidence` values of the
+// all-zero difference row back it up, since any n values fit some polynomial of degree n - 1.
+pub fn analyse(s: &str, min_evidence: usize) -> Vec<Report> {
+    s.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(line, values)| {
+            let values = parse_history(values);
+            let history = History::classify(&values);
+            let justified = match &history {
+                History::Polynomial(polynomial) => polynomial.evidence >= min_evidence,
+                History::Geometric(_) => true,
+                History::Unknown => false,
+            };
+            let next = history.at(values.len() as i32);
+            let previous = history.at(-1);
+            Report { line, history, justified, next, previous }
+        })
+        .collect()
+}
+
+pub fn parse_history(line: &str) -> Vec<BigInt> {
+    line.split_whitespace()
+        .map(|num| num.parse().unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::One;
+
+    fn fit(values: &[i64]) -> Result<Polynomial, NotPolynomial> {
+        Polynomial::fit(&values.iter().map(|&value| BigInt::from(value)).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn it_extrapolates() {
+        let polynomial = fit(&[10, 13, 16, 21, 30, 45]).unwrap();
+        assert_eq!(polynomial.degree, 3);
+        assert_eq!(polynomial.at(6), BigInt::from(68));
+        assert_eq!(polynomial.at(-1), BigInt::from(5));
+        assert_eq!(fit(&[0, 3, 6, 9, 12, 15]).unwrap().at(-1), BigInt::from(-3));
+        assert_eq!(fit(&[7, 7]).unwrap().degree, 0);
+        assert_eq!(fit(&[0, 0, 0]).unwrap().at(100), BigInt::zero());
+    }
+
+    #[test]
+    fn it_extrapolates_far_away() {
+        let squares = fit(&[0, 1, 4, 9]).unwrap();
+        let far = BigInt::from(10).pow(12);
+        assert_eq!(squares.at(far.clone()), &far * &far);
+        assert_eq!(squares.at(-far.clone()), &far * &far);
+        let cubes = fit(&[-8, -1, 0, 1, 8, 27]).unwrap();
+        assert_eq!(cubes.at(far.clone() + 2), &far * &far * &far);
+    }
+
+    #[test]
+    fn it_rejects_non_polynomial() {
+        assert_eq!(fit(&[1, 2, 4, 8, 16]), Err(NotPolynomial { len: 5 }));
+        assert_eq!(fit(&[1, 5]), Err(NotPolynomial { len: 2 }));
+        assert_eq!(fit(&[]), Err(NotPolynomial { len: 0 }));
+    }
+
+    #[test]
+    fn it_classifies_histories() {
+        let reports = analyse("0 3 6 9 12 15\n1 2 4 8 16\n16 -8 4 -2\n1 1 2 3 5 8\n1 4 9 16\n", 2);
+        let kinds = reports.iter().map(|report| report.history.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                "polynomial of degree 1",
+                "geometric with ratio 2",
+                "geometric with ratio -1/2",
+                "unknown",
+                "polynomial of degree 2",
+            ]
+        );
+        let flagged = reports.iter().filter(|report| !report.justified).map(|report| report.line).collect::<Vec<_>>();
+        assert_eq!(flagged, vec![3, 4]);
+        assert_eq!(reports[0].next, Some(BigRational::from(BigInt::from(18))));
+        assert_eq!(reports[1].next, Some(BigRational::from(BigInt::from(32))));
+        assert_eq!(reports[1].previous, Some(BigRational::new(BigInt::one(), BigInt::from(2))));
+        assert_eq!(reports[2].next, Some(BigRational::from(BigInt::from(1))));
+        assert_eq!(reports[3].next, None);
+    }
+
+    #[test]
+    fn it_handles_big_values() {
+        let reports = analyse("9223372036854775807 9223372036854775807 9223372036854775807", 1);
+        let expected = BigRational::from(BigInt::from(i64::MAX));
+        assert_eq!(reports[0].next, Some(expected));
+        let line = (1..=4_u32).map(|i| (BigInt::from(10).pow(30) * i).to_string()).join(" ");
+        let reports = analyse(&line, 1);
+        assert_eq!(reports[0].next, Some(BigRational::from(BigInt::from(10).pow(30) * 5)));
+    }
+}
@@ -0,0 +1,484 @@
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+
+const TILE: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    South,
+    West,
+    East,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::North, Direction::South, Direction::West, Direction::East];
+
+    fn delta(&self) -> [isize; 2] {
+        match self {
+            Direction::North => [0, -1],
+            Direction::South => [0, 1],
+            Direction::West => [-1, 0],
+            Direction::East => [1, 0],
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+            Direction::East => Direction::West,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pipe {
+    Start,
+    Vertical,
+    Horizontal,
+    NE,
+    NW,
+    SW,
+    SE,
+    Ground
+}
+
+impl Pipe {
+    pub const PIPES: [Pipe; 6] = [Pipe::Vertical, Pipe::Horizontal, Pipe::NE, Pipe::NW, Pipe::SW, Pipe::SE];
+
+    pub fn from(pipe: char) -> Pipe {
+        match pipe {
+            '|' => Pipe::Vertical,
+            '-' => Pipe::Horizontal,
+            'L' => Pipe::NE,
+            'J' => Pipe::NW,
+            '7' => Pipe::SW,
+            'F' => Pipe::SE,
+            'S' => Pipe::Start,
+            '.' => Pipe::Ground,
+            _ => unreachable!()
+        }
+    }
+
+    pub fn connections(&self) -> &'static [Direction] {
+        match self {
+            Pipe::Vertical => &[Direction::North, Direction::South],
+            Pipe::Horizontal => &[Direction::West, Direction::East],
+            Pipe::NE => &[Direction::North, Direction::East],
+            Pipe::NW => &[Direction::North, Direction::West],
+            Pipe::SW => &[Direction::South, Direction::West],
+            Pipe::SE => &[Direction::South, Direction::East],
+            Pipe::Start | Pipe::Ground => &[],
+        }
+    }
+
+    pub fn connects(&self, direction: Direction) -> bool {
+        self.connections().contains(&direction)
+    }
+
+    pub fn glyph(&self, heavy: bool) -> char {
+        let (light, heavy_glyph) = match self {
+            Pipe::Vertical => ('│', '┃'),
+            Pipe::Horizontal => ('─', '━'),
+            Pipe::NE => ('└', '┗'),
+            Pipe::NW => ('┘', '┛'),
+            Pipe::SW => ('┐', '┓'),
+            Pipe::SE => ('┌', '┏'),
+            Pipe::Start => ('S', 'S'),
+            Pipe::Ground => ('·', '·'),
+        };
+        if heavy { heavy_glyph } else { light }
+    }
+}
+
+fn shoelace(vertices: &[[usize; 2]]) -> usize {
+    if vertices.is_empty() {
+        return 0;
+    }
+    let double_area: isize = vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|([x1, y1], [x2, y2])| (*x1 * *y2) as isize - (*x2 * *y1) as isize)
+        .sum();
+    (double_area.unsigned_abs() + 2 - vertices.len()) / 2
+}
+
+// Enclosed tiles count every tile inside the loop, including pipes of nested loops.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipeLoop {
+    pub vertices: Vec<[usize; 2]>,
+    pub enclosed: usize,
+}
+
+impl PipeLoop {
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PipeMaze {
+    pub tiles: Vec<Vec<Pipe>>,
+    pub start: Option<[usize; 2]>,
+}
+
+impl PipeMaze {
+    pub fn parse(s: &str) -> Self {
+        let tiles: Vec<Vec<Pipe>> = s.lines()
+            .map(|line| line.chars().map(Pipe::from).collect())
+            .collect();
+        let starts: Vec<[usize; 2]> = tiles.iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.iter()
+                .enumerate()
+                .filter(|(_, pipe)| matches!(pipe, Pipe::Start))
+                .map(move |(x, _)| [x, y]))
+            .collect();
+        let mut maze = PipeMaze { tiles, start: starts.first().copied() };
+        for start in starts {
+            maze.infer_start(start);
+        }
+        maze
+    }
+
+    // Tries every pipe whose ends both meet a neighbour pointing back at `S` and keeps
+    // the first one that closes a loop, so extra pipes next to `S` can't mislead it.
+    fn infer_start(&mut self, start: [usize; 2]) {
+        let [x, y] = start;
+        let candidates = Pipe::PIPES.iter().filter(|pipe| {
+            pipe.connections().iter().all(|&direction| {
+                self.neighbour(start, direction)
+                    .is_some_and(|[nx, ny]| self.tiles[ny][nx].connects(direction.opposite()))
+            })
+        }).copied().collect::<Vec<_>>();
+        for pipe in candidates {
+            self.tiles[y][x] = pipe;
+            if self.trace_loop(start).is_some() {
+                return;
+            }
+        }
+        self.tiles[y][x] = Pipe::Ground;
+    }
+
+    pub fn width(&self) -> usize {
+        self.tiles[0].len()
+    }
+
+    pub fn height(&self) -> usize {
+        self.tiles.len()
+    }
+
+    fn neighbour(&self, [x, y]: [usize; 2], direction: Direction) -> Option<[usize; 2]> {
+        let [dx, dy] = direction.delta();
+        let (nx, ny) = (x.wrapping_add_signed(dx), y.wrapping_add_signed(dy));
+        if (0..self.width()).contains(&nx) && (0..self.height()).contains(&ny) {
+            Some([nx, ny])
+        } else {
+            None
+        }
+    }
+
+    pub fn connected(&self, point: [usize; 2], direction: Direction) -> Option<[usize; 2]> {
+        let [x, y] = point;
+        if !self.tiles[y][x].connects(direction) {
+            return None;
+        }
+        self.neighbour(point, direction)
+            .filter(|&[nx, ny]| self.tiles[ny][nx].connects(direction.opposite()))
+    }
+
+    pub fn trace_loop(&self, from: [usize; 2]) -> Option<Vec<[usize; 2]>> {
+        let [x, y] = from;
+        let mut direction = *self.tiles[y][x].connections().first()?;
+        let mut cur = from;
+        let mut res = vec![];
+        loop {
+            res.push(cur);
+            cur = self.connected(cur, direction)?;
+            if cur == from {
+                return Some(res);
+            }
+            let [x, y] = cur;
+            direction = *self.tiles[y][x]
+                .connections()
+                .iter()
+                .find(|&&next| next != direction.opposite())?;
+        }
+    }
+
+    pub fn main_loop(&self) -> Vec<[usize; 2]> {
+        self.start.and_then(|start| self.trace_loop(start)).unwrap_or_default()
+    }
+
+    pub fn farthest_distance(&self) -> usize {
+        self.main_loop().len() / 2
+    }
+
+    // Pick's theorem: A = i + b / 2 - 1, with A from the shoelace formula.
+    pub fn enclosed_by_shoelace(&self) -> usize {
+        shoelace(&self.main_loop())
+    }
+
+    pub fn inside_tiles(&self) -> Vec<Vec<bool>> {
+        let on_loop = self.on_main_loop();
+        self.tiles
+            .iter()
+            .zip(on_loop.iter())
+            .map(|(row, on_loop_row)| {
+                let mut inside = false;
+                row.iter()
+                    .zip(on_loop_row.iter())
+                    .map(|(pipe, &on_loop)| {
+                        if on_loop {
+                            inside ^= pipe.connects(Direction::North);
+                            false
+                        } else {
+                            inside
+                        }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn components(&self) -> Vec<Vec<[usize; 2]>> {
+        let mut seen = vec![vec![false; self.width()]; self.height()];
+        let mut res = vec![];
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                if seen[y][x] || self.tiles[y][x].connections().is_empty() {
+                    continue;
+                }
+                seen[y][x] = true;
+                let mut component = vec![];
+                let mut stack = vec![[x, y]];
+                while let Some(point) = stack.pop() {
+                    component.push(point);
+                    for direction in Direction::ALL {
+                        if let Some([nx, ny]) = self.connected(point, direction) {
+                            if !seen[ny][nx] {
+                                seen[ny][nx] = true;
+                                stack.push([nx, ny]);
+                            }
+                        }
+                    }
+                }
+                res.push(component);
+            }
+        }
+        res
+    }
+
+    fn degree(&self, point: [usize; 2]) -> usize {
+        Direction::ALL.iter().filter(|&&direction| self.connected(point, direction).is_some()).count()
+    }
+
+    pub fn loops(&self) -> Vec<PipeLoop> {
+        self.components()
+            .into_iter()
+            .filter_map(|component| self.trace_loop(component[0]))
+            .map(|vertices| PipeLoop { enclosed: shoelace(&vertices), vertices })
+            .collect()
+    }
+
+    // Pipe chains that don't close, ordered from one loose end to the other.
+    pub fn dangling_segments(&self) -> Vec<Vec<[usize; 2]>> {
+        self.components()
+            .into_iter()
+            .filter(|component| self.trace_loop(component[0]).is_none())
+            .map(|component| {
+                let end = *component.iter().find(|&&point| self.degree(point) < 2).unwrap();
+                let mut segment = vec![end];
+                let mut prev = None;
+                let mut cur = end;
+                while let Some(next) = Direction::ALL
+                    .iter()
+                    .filter_map(|&direction| self.connected(cur, direction))
+                    .find(|&next| Some(next) != prev)
+                {
+                    prev = Some(cur);
+                    cur = next;
+                    segment.push(cur);
+                }
+                segment
+            })
+            .collect()
+    }
+
+    // Main loop in heavy lines, enclosed tiles shaded, everything outside in light lines.
+    pub fn render_text(&self) -> String {
+        let on_loop = self.on_main_loop();
+        let inside = self.inside_tiles();
+        let mut res = String::new();
+        for (y, row) in self.tiles.iter().enumerate() {
+            for (x, pipe) in row.iter().enumerate() {
+                res.push(match (on_loop[y][x], inside[y][x]) {
+                    (true, _) => pipe.glyph(true),
+                    (false, true) => '▓',
+                    (false, false) => pipe.glyph(false),
+                });
+            }
+            res.push('\n');
+        }
+        res
+    }
+
+    pub fn render_svg(&self) -> String {
+        let on_loop = self.on_main_loop();
+        let inside = self.inside_tiles();
+        let (width, height) = (self.width() * TILE, self.height() * TILE);
+        let mut res = String::new();
+        writeln!(res, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#).unwrap();
+        writeln!(res, r#"<rect width="{width}" height="{height}" fill="white"/>"#).unwrap();
+        for (y, row) in inside.iter().enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|(_, &inside)| inside) {
+                writeln!(res, r#"<rect x="{}" y="{}" width="{TILE}" height="{TILE}" fill="lightgreen"/>"#, x * TILE, y * TILE).unwrap();
+            }
+        }
+        for (y, row) in self.tiles.iter().enumerate() {
+            for (x, pipe) in row.iter().enumerate() {
+                let (cx, cy) = (x * TILE + TILE / 2, y * TILE + TILE / 2);
+                let (stroke, stroke_width) = if on_loop[y][x] { ("black", 3) } else { ("gray", 1) };
+                for direction in pipe.connections() {
+                    let [dx, dy] = direction.delta();
+                    let (ex, ey) = (
+                        cx.wrapping_add_signed(dx * TILE as isize / 2),
+                        cy.wrapping_add_signed(dy * TILE as isize / 2),
+                    );
+                    writeln!(
+                        res,
+                        r#"<line x1="{cx}" y1="{cy}" x2="{ex}" y2="{ey}" stroke="{stroke}" stroke-width="{stroke_width}" stroke-linecap="round"/>"#
+                    ).unwrap();
+                }
+            }
+        }
+        res.push_str("</svg>\n");
+        res
+    }
+
+    pub fn write_svg(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.render_svg())
+    }
+
+    fn on_main_loop(&self) -> Vec<Vec<bool>> {
+        let mut on_loop = vec![vec![false; self.width()]; self.height()];
+        for [x, y] in self.main_loop() {
+            on_loop[y][x] = true;
+        }
+        on_loop
+    }
+
+    pub fn enclosed_by_scanline(&self) -> usize {
+        self.inside_tiles()
+            .iter()
+            .map(|row| row.iter().filter(|&&inside| inside).count())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_extracts_main_loop() {
+        let maze = PipeMaze::parse(
+"-L|F7
+7S-7|
+L|7||
+-L-J|
+L|-JF");
+        assert_eq!(maze.tiles[1][1], Pipe::SE);
+        assert_eq!(maze.main_loop(), vec![[1, 1], [1, 2], [1, 3], [2, 3], [3, 3], [3, 2], [3, 1], [2, 1]]);
+        assert_eq!(maze.farthest_distance(), 4);
+        assert_eq!(maze.enclosed_by_shoelace(), 1);
+        assert_eq!(maze.enclosed_by_scanline(), 1);
+    }
+
+    #[test]
+    fn it_cross_checks_enclosed_area() {
+        let inputs = [
+("...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........", 4),
+(".F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...", 8),
+        ];
+        for (input, enclosed) in inputs {
+            let maze = PipeMaze::parse(input);
+            assert_eq!(maze.enclosed_by_shoelace(), enclosed);
+            assert_eq!(maze.enclosed_by_scanline(), enclosed);
+        }
+    }
+
+    #[test]
+    fn it_finds_every_loop() {
+        let maze = PipeMaze::parse(
+"F----7.F7
+|F--7|.||
+||F7||.LJ
+||LJ||...
+|L--J|F-.
+L----J|.S");
+        assert_eq!(maze.start, Some([8, 5]));
+        assert_eq!(maze.tiles[5][8], Pipe::Ground);
+        assert!(maze.main_loop().is_empty());
+        let loops = maze.loops();
+        let summary = loops.iter().map(|pipe_loop| (pipe_loop.len(), pipe_loop.enclosed)).collect::<Vec<_>>();
+        assert_eq!(summary, vec![(20, 16), (6, 0), (12, 4), (4, 0)]);
+        assert_eq!(maze.dangling_segments(), vec![vec![[7, 4], [6, 4], [6, 5]]]);
+    }
+
+    #[test]
+    fn it_renders_maze() {
+        let maze = PipeMaze::parse(
+"..........
+.S------7.
+.|F----7|.
+.||-...||.
+.||....||.
+.|L-7F-J|.
+.|..||..|.
+.L--JL--J.
+..........");
+        assert_eq!(
+            maze.render_text(),
+"··········
+·┏━━━━━━┓·
+·┃┏━━━━┓┃·
+·┃┃─...┃┃·
+·┃┃....┃┃·
+·┃┗━┓┏━┛┃·
+·┃▓▓┃┃▓▓┃·
+·┗━━┛┗━━┛·
+··········
+".replace('.', "·")
+        );
+        let svg = maze.render_svg();
+        assert!(svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="90""#));
+        assert_eq!(svg.matches("lightgreen").count(), 4);
+        assert_eq!(svg.matches(r#"stroke="black""#).count(), 2 * maze.main_loop().len());
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
@@ -0,0 +1,210 @@
+use std::collections::BTreeMap;
+
+// Each empty row (column) grows into `y` (`x`) rows (columns) when the universe expands;
+// a factor of 0 collapses empty lines away entirely.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Expansion {
+    pub x: u64,
+    pub y: u64,
+}
+
+impl Expansion {
+    pub fn uniform(factor: u64) -> Self {
+        Expansion { x: factor, y: factor }
+    }
+}
+
+// Only galaxy coordinates are kept, plus for every axis the number of empty
+// lines strictly before each index, so the grid itself is never materialised.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Universe {
+    pub galaxies: Vec<[usize; 2]>,
+    empty_before: [Vec<usize>; 2],
+}
+
+impl Universe {
+    pub fn parse(s: &str) -> Self {
+        let galaxies: Vec<[usize; 2]> = s
+            .lines()
+            .enumerate()
+            .flat_map(|(y, line)| line
+                .char_indices()
+                .filter_map(move |(x, c)| (c == '#').then_some([x, y])))
+            .collect();
+        let height = s.lines().count();
+        let width = s.lines().map(str::len).max().unwrap_or(0);
+        let empty_before = [width, height].map(|len| Vec::with_capacity(len + 1));
+        let mut universe = Universe { galaxies, empty_before };
+        for (axis, len) in [width, height].into_iter().enumerate() {
+            let mut occupied = vec![false; len];
+            for galaxy in &universe.galaxies {
+                occupied[galaxy[axis]] = true;
+            }
+            let prefix = &mut universe.empty_before[axis];
+            prefix.push(0);
+            for (i, is_occupied) in occupied.into_iter().enumerate() {
+                prefix.push(prefix[i] + usize::from(!is_occupied));
+            }
+        }
+        universe
+    }
+
+    // Lines before a coordinate are either occupied, kept as they are, or empty and
+    // scaled by the factor; there are never more empty lines than lines.
+    pub fn expand(&self, coord: [usize; 2], expansion: Expansion) -> [u64; 2] {
+        let factors = [expansion.x, expansion.y];
+        [0, 1].map(|axis| {
+            let empty = self.empty_before[axis][coord[axis]];
+            (coord[axis] - empty) as u64 + empty as u64 * factors[axis]
+        })
+    }
+
+    pub fn expanded(&self, expansion: Expansion) -> Vec<[u64; 2]> {
+        self.galaxies.iter().map(|&galaxy| self.expand(galaxy, expansion)).collect()
+    }
+
+    // Manhattan distances split per axis; once an axis is sorted, the i-th value is
+    // the larger end of exactly i pairs, so the sum is sum(i * v_i) - sum(v_j, j < i).
+    pub fn distance_sum(&self, expansion: Expansion) -> u64 {
+        let expanded = self.expanded(expansion);
+        (0..2)
+            .map(|axis| {
+                let mut coords: Vec<u64> = expanded.iter().map(|coord| coord[axis]).collect();
+                coords.sort_unstable();
+                let mut prefix = 0;
+                let mut res = 0;
+                for (i, coord) in coords.into_iter().enumerate() {
+                    res += coord * i as u64 - prefix;
+                    prefix += coord;
+                }
+                res
+            })
+            .sum()
+    }
+
+    // Galaxy ids are indices into `galaxies`, i.e. reading order.
+    pub fn distance(&self, a: usize, b: usize, expansion: Expansion) -> u64 {
+        let ([x1, y1], [x2, y2]) = (
+            self.expand(self.galaxies[a], expansion),
+            self.expand(self.galaxies[b], expansion),
+        );
+        x1.abs_diff(x2) + y1.abs_diff(y2)
+    }
+
+    pub fn distance_matrix(&self, expansion: Expansion) -> Vec<Vec<u64>> {
+        let expanded = self.expanded(expansion);
+        expanded
+            .iter()
+            .map(|[x1, y1]| expanded.iter().map(|[x2, y2]| x1.abs_diff(*x2) + y1.abs_diff(*y2)).collect())
+            .collect()
+    }
+
+    // Ties go to the lowest id; a lone galaxy has no neighbours at all.
+    pub fn neighbours(&self, expansion: Expansion) -> Vec<Option<Neighbours>> {
+        self.distance_matrix(expansion)
+            .iter()
+            .enumerate()
+            .map(|(id, row)| {
+                let others = row.iter().copied().enumerate().filter(|&(other, _)| other != id);
+                let nearest = others.clone().min_by_key(|&(other, distance)| (distance, other))?;
+                let farthest = others.min_by_key(|&(other, distance)| (u64::MAX - distance, other))?;
+                Some(Neighbours { nearest, farthest })
+            })
+            .collect()
+    }
+
+    // Number of galaxy pairs at every distance that occurs.
+    pub fn distance_histogram(&self, expansion: Expansion) -> BTreeMap<u64, usize> {
+        let mut histogram = BTreeMap::new();
+        for (id, row) in self.distance_matrix(expansion).iter().enumerate() {
+            for &distance in &row[id + 1..] {
+                *histogram.entry(distance).or_insert(0) += 1;
+            }
+        }
+        histogram
+    }
+}
+
+// (galaxy id, distance) of the closest and the most remote other galaxy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Neighbours {
+    pub nearest: (usize, u64),
+    pub farthest: (usize, u64),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str =
+"...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    fn brute_force(universe: &Universe, expansion: Expansion) -> u64 {
+        let expanded = universe.expanded(expansion);
+        let mut res = 0;
+        for (i, [x1, y1]) in expanded.iter().enumerate() {
+            for [x2, y2] in &expanded[i + 1..] {
+                res += x1.abs_diff(*x2) + y1.abs_diff(*y2);
+            }
+        }
+        res
+    }
+
+    #[test]
+    fn it_expands_sparsely() {
+        let universe = Universe::parse(TEST_INPUT);
+        assert_eq!(universe.galaxies.len(), 9);
+        assert_eq!(universe.expand([9, 9], Expansion::uniform(2)), [12, 11]);
+        assert_eq!(universe.expand([0, 2], Expansion { x: 1, y: 10 }), [0, 2]);
+        assert_eq!(universe.distance_sum(Expansion::uniform(1)), 292);
+        assert_eq!(universe.distance_sum(Expansion::uniform(2)), 374);
+        assert_eq!(universe.distance_sum(Expansion::uniform(10)), 1030);
+        assert_eq!(universe.distance_sum(Expansion::uniform(100)), 8410);
+        assert_eq!(universe.expand([9, 9], Expansion::uniform(0)), [6, 7]);
+        assert_eq!(universe.distance_sum(Expansion::uniform(0)), 210);
+    }
+
+    #[test]
+    fn it_expands_axes_independently() {
+        let universe = Universe::parse(TEST_INPUT);
+        for expansion in [Expansion { x: 0, y: 7 }, Expansion { x: 1, y: 7 }, Expansion { x: 5, y: 2 }, Expansion { x: 1_000_000, y: 3 }] {
+            assert_eq!(universe.distance_sum(expansion), brute_force(&universe, expansion));
+        }
+        let rows_only = universe.distance_sum(Expansion { x: 1, y: 2 });
+        let columns_only = universe.distance_sum(Expansion { x: 2, y: 1 });
+        assert_eq!(rows_only + columns_only - 292, 374);
+    }
+
+    #[test]
+    fn it_answers_pairwise_queries() {
+        let universe = Universe::parse(TEST_INPUT);
+        let expansion = Expansion::uniform(2);
+        assert_eq!(universe.distance(4, 8, expansion), 9);
+        assert_eq!(universe.distance(0, 6, expansion), 15);
+        assert_eq!(universe.distance(2, 5, expansion), 17);
+        assert_eq!(universe.distance(7, 8, expansion), 5);
+        let matrix = universe.distance_matrix(expansion);
+        assert_eq!(matrix[8][4], 9);
+        assert!((0..9).all(|id| matrix[id][id] == 0));
+        let total: u64 = matrix.iter().flatten().sum();
+        assert_eq!(total, 2 * universe.distance_sum(expansion));
+
+        let neighbours = universe.neighbours(expansion);
+        assert_eq!(neighbours[7], Some(Neighbours { nearest: (8, 5), farthest: (1, 19) }));
+        assert_eq!(Universe::parse("..#..").neighbours(expansion), vec![None]);
+
+        let histogram = universe.distance_histogram(expansion);
+        assert_eq!(histogram.values().sum::<usize>(), 36);
+        assert_eq!(histogram.keys().next(), Some(&5));
+        assert_eq!(histogram.keys().last(), Some(&19));
+    }
+}
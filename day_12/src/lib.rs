@@ -0,0 +1,401 @@
+use itertools::Itertools;
+use nom::{
+    character::complete::{space1, char as nom_char, u64 as nom_u64},
+    IResult,
+    multi::{separated_list1, many1},
+    branch::alt, sequence::preceded
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub springs: Vec<char>,
+    pub groups: Vec<usize>,
+}
+
+fn parse_record(input: &str) -> IResult<&str, Record> {
+    let (input, springs) = many1(alt((nom_char('?'), nom_char('.'), nom_char('#'))))(input)?;
+    let (input, groups) = preceded(space1, separated_list1(nom_char(','), nom_u64))(input)?;
+    let groups = groups.into_iter().map(|group| group as usize).collect();
+    Ok((input, Record { springs, groups }))
+}
+
+impl Record {
+    pub fn parse(line: &str) -> Self {
+        parse_record(line).unwrap().1
+    }
+
+    // `factor` copies of the record, springs joined by '?' and groups concatenated.
+    pub fn unfold(&self, factor: usize) -> Self {
+        let mut springs = vec![];
+        for copy in 0..factor {
+            if copy > 0 {
+                springs.push('?');
+            }
+            springs.extend(&self.springs);
+        }
+        Record { springs, groups: self.groups.repeat(factor) }
+    }
+
+    // Where the group starting at `pos` would let the next one start, if it fits there:
+    // no '.' inside it and no '#' right after it.
+    fn group_end(&self, pos: usize, len: usize, runs: &[usize]) -> Option<usize> {
+        let end = pos + len;
+        if runs[pos] < len {
+            None
+        } else if end == self.springs.len() {
+            Some(end)
+        } else if self.springs[end] != '#' {
+            Some(end + 1)
+        } else {
+            None
+        }
+    }
+
+    // runs[pos] is the length of the longest '.'-free stretch starting at pos.
+    fn runs(&self) -> Vec<usize> {
+        let len = self.springs.len();
+        let mut runs = vec![0; len + 1];
+        for pos in (0..len).rev() {
+            runs[pos] = if self.springs[pos] == '.' { 0 } else { runs[pos + 1] + 1 };
+        }
+        runs
+    }
+
+    // ways[pos][group] is the number of ways to fill springs[pos..] with groups[group..].
+    pub fn ways(&self) -> Vec<Vec<u128>> {
+        let (len, groups) = (self.springs.len(), self.groups.len());
+        let runs = self.runs();
+        let mut ways = vec![vec![0_u128; groups + 1]; len + 1];
+        ways[len][groups] = 1;
+        for pos in (0..len).rev() {
+            for group in 0..=groups {
+                let mut res = 0;
+                if self.springs[pos] != '#' {
+                    res += ways[pos + 1][group];
+                }
+                if group < groups && self.springs[pos] != '.' {
+                    if let Some(next) = self.group_end(pos, self.groups[group], &runs) {
+                        res += ways[next][group + 1];
+                    }
+                }
+                ways[pos][group] = res;
+            }
+        }
+        ways
+    }
+
+    pub fn count(&self) -> u128 {
+        self.ways()[0][0]
+    }
+
+    // Arrangements in lexicographic order, where '#' sorts before '.': at every
+    // position the choice of starting the next group there comes first.
+    fn nth_with(&self, ways: &[Vec<u128>], mut k: u128) -> Option<Vec<char>> {
+        if k >= ways[0][0] {
+            return None;
+        }
+        let (len, groups) = (self.springs.len(), self.groups.len());
+        let runs = self.runs();
+        let mut res = Vec::with_capacity(len);
+        let (mut pos, mut group) = (0, 0);
+        while pos < len {
+            if group < groups && self.springs[pos] != '.' {
+                if let Some(next) = self.group_end(pos, self.groups[group], &runs) {
+                    let placed = ways[next][group + 1];
+                    if k < placed {
+                        res.resize(pos + self.groups[group], '#');
+                        res.resize(next, '.');
+                        pos = next;
+                        group += 1;
+                        continue;
+                    }
+                    k -= placed;
+                }
+            }
+            res.push('.');
+            pos += 1;
+        }
+        Some(res)
+    }
+
+    pub fn nth_arrangement(&self, k: u128) -> Option<Vec<char>> {
+        self.nth_with(&self.ways(), k)
+    }
+
+    pub fn arrangements(&self) -> impl Iterator<Item = Vec<char>> + '_ {
+        let ways = self.ways();
+        (0..ways[0][0]).map(move |k| self.nth_with(&ways, k).unwrap())
+    }
+
+    // Uniform over all arrangements given a source of uniform u64s; candidates past the
+    // largest multiple of the count are rejected so that no arrangement is favoured.
+    pub fn sample(&self, mut next_u64: impl FnMut() -> u64) -> Option<Vec<char>> {
+        let ways = self.ways();
+        let count = ways[0][0];
+        if count == 0 {
+            return None;
+        }
+        let limit = u128::MAX - u128::MAX % count;
+        loop {
+            let candidate = (next_u64() as u128) << 64 | next_u64() as u128;
+            if candidate < limit {
+                return self.nth_with(&ways, candidate % count);
+            }
+        }
+    }
+}
+
+pub fn is_valid(springs: &[char], groups: &[usize]) -> bool {
+    let actual = springs
+        .iter()
+        .group_by(|&&c| c)
+        .into_iter()
+        .filter(|(c, _)| *c == '#')
+        .map(|(_, group)| group.count())
+        .collect::<Vec<_>>();
+    !springs.contains(&'?') && actual == groups
+}
+
+pub fn count_arrangements(s: &str, factor: usize) -> u128 {
+    s.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| Record::parse(line).unfold(factor).count())
+        .sum()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    Unique(Vec<Vec<char>>),
+    Ambiguous([Vec<Vec<char>>; 2]),
+    Contradiction,
+}
+
+// Every row and column of a nonogram is a spring record whose springs are the
+// cells, so line constraints are checked with the same arrangement counter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Nonogram {
+    pub rows: Vec<Vec<usize>>,
+    pub columns: Vec<Vec<usize>>,
+}
+
+impl Nonogram {
+    pub fn new(rows: Vec<Vec<usize>>, columns: Vec<Vec<usize>>) -> Self {
+        Nonogram { rows, columns }
+    }
+
+    // Settles every '?' of the line that takes the same value in all its arrangements;
+    // None when the line has no arrangement left.
+    fn propagate_line(line: &mut [char], groups: &[usize]) -> Option<bool> {
+        let mut record = Record { springs: line.to_vec(), groups: groups.to_vec() };
+        if record.count() == 0 {
+            return None;
+        }
+        let mut changed = false;
+        for (i, cell) in line.iter_mut().enumerate() {
+            if *cell != '?' {
+                continue;
+            }
+            let mut possible = ['#', '.'].into_iter().filter(|&c| {
+                record.springs[i] = c;
+                record.count() > 0
+            });
+            let first = possible.next().unwrap();
+            if possible.next().is_none() {
+                *cell = first;
+                changed = true;
+            }
+            record.springs[i] = *cell;
+        }
+        Some(changed)
+    }
+
+    // Propagates rows and columns until nothing changes.
+    pub fn propagate(&self, grid: &mut [Vec<char>]) -> Option<()> {
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for (y, groups) in self.rows.iter().enumerate() {
+                changed |= Self::propagate_line(&mut grid[y], groups)?;
+            }
+            for (x, groups) in self.columns.iter().enumerate() {
+                let mut column = grid.iter().map(|row| row[x]).collect::<Vec<_>>();
+                changed |= Self::propagate_line(&mut column, groups)?;
+                for (row, c) in grid.iter_mut().zip(column) {
+                    row[x] = c;
+                }
+            }
+        }
+        Some(())
+    }
+
+    // Line propagation alone can stall, so the first unsettled cell is guessed both
+    // ways; the search stops as soon as a second solution turns up.
+    fn search(&self, mut grid: Vec<Vec<char>>, found: &mut Vec<Vec<Vec<char>>>) {
+        if found.len() >= 2 || self.propagate(&mut grid).is_none() {
+            return;
+        }
+        let unknown = grid
+            .iter()
+            .enumerate()
+            .find_map(|(y, row)| row.iter().position(|&c| c == '?').map(|x| [x, y]));
+        match unknown {
+            None => found.push(grid),
+            Some([x, y]) => {
+                for c in ['#', '.'] {
+                    let mut guess = grid.clone();
+                    guess[y][x] = c;
+                    self.search(guess, found);
+                }
+            }
+        }
+    }
+
+    pub fn solve(&self) -> Solution {
+        let grid = vec![vec!['?'; self.columns.len()]; self.rows.len()];
+        let mut found = vec![];
+        self.search(grid, &mut found);
+        let mut found = found.into_iter();
+        match (found.next(), found.next()) {
+            (None, _) => Solution::Contradiction,
+            (Some(grid), None) => Solution::Unique(grid),
+            (Some(first), Some(second)) => Solution::Ambiguous([first, second]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str =
+"???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1";
+
+    fn brute_force(record: &Record) -> u128 {
+        let unknowns: Vec<usize> = (0..record.springs.len()).filter(|&i| record.springs[i] == '?').collect();
+        (0..1_u32 << unknowns.len())
+            .filter(|mask| {
+                let mut springs = record.springs.clone();
+                for (bit, &i) in unknowns.iter().enumerate() {
+                    springs[i] = if mask >> bit & 1 == 1 { '#' } else { '.' };
+                }
+                let groups: Vec<usize> = springs
+                    .split(|&c| c == '.')
+                    .filter(|run| !run.is_empty())
+                    .map(<[char]>::len)
+                    .collect();
+                groups == record.groups
+            })
+            .count() as u128
+    }
+
+    #[test]
+    fn it_counts_arrangements() {
+        let counts: Vec<u128> = TEST_INPUT.lines().map(|line| Record::parse(line).count()).collect();
+        assert_eq!(counts, vec![1, 4, 1, 1, 4, 10]);
+        let unfolded: Vec<u128> = TEST_INPUT.lines().map(|line| Record::parse(line).unfold(5).count()).collect();
+        assert_eq!(unfolded, vec![1, 16384, 1, 16, 2500, 506250]);
+        assert_eq!(count_arrangements(TEST_INPUT, 1), 21);
+        assert_eq!(count_arrangements(TEST_INPUT, 5), 525152);
+    }
+
+    #[test]
+    fn it_matches_brute_force() {
+        for line in TEST_INPUT.lines().chain(["#?#?.?#? 1,1,2", "??????? 2,1", "#??#. 4", "..... 1", "?#? 1,1"]) {
+            let record = Record::parse(line);
+            assert_eq!(record.count(), brute_force(&record), "{line}");
+            let unfolded = record.unfold(2);
+            if unfolded.springs.iter().filter(|&&c| c == '?').count() <= 16 {
+                assert_eq!(unfolded.count(), brute_force(&unfolded), "{line}");
+            }
+        }
+    }
+
+    #[test]
+    fn it_unfolds_any_number_of_times() {
+        let record = Record::parse("???.### 1,1,3");
+        assert_eq!(record.unfold(0).count(), 1);
+        assert_eq!(record.unfold(3).springs.iter().collect::<String>(), "???.###????.###????.###");
+        let huge = Record::parse("?????????? 1").unfold(16);
+        assert!(huge.count() > u64::MAX as u128);
+    }
+
+    #[test]
+    fn it_enumerates_arrangements() {
+        for line in TEST_INPUT.lines().chain(["#?#?.?#? 1,1,2", "??????? 2,1", "..... 1"]) {
+            let record = Record::parse(line);
+            let arrangements = record.arrangements().collect::<Vec<_>>();
+            assert_eq!(arrangements.len() as u128, record.count(), "{line}");
+            assert!(arrangements.iter().all(|springs| is_valid(springs, &record.groups)), "{line}");
+            assert!(arrangements.windows(2).all(|pair| pair[0] < pair[1]), "{line}");
+            let fits = |springs: &Vec<char>| springs.iter().zip(&record.springs).all(|(c, r)| r == &'?' || r == c);
+            assert!(arrangements.iter().all(fits), "{line}");
+        }
+        let record = Record::parse("?###???????? 3,2,1");
+        let as_string = |springs: Option<Vec<char>>| springs.map(|springs| springs.into_iter().collect::<String>());
+        assert_eq!(as_string(record.nth_arrangement(0)), Some(".###.##.#...".to_string()));
+        assert_eq!(as_string(record.nth_arrangement(9)), Some(".###....##.#".to_string()));
+        assert_eq!(record.nth_arrangement(10), None);
+    }
+
+    #[test]
+    fn it_samples_uniformly() {
+        let record = Record::parse("?###???????? 3,2,1");
+        let mut state = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next_u64 = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let mut seen = std::collections::HashMap::new();
+        for _ in 0..10_000 {
+            let springs = record.sample(&mut next_u64).unwrap();
+            assert!(is_valid(&springs, &record.groups));
+            *seen.entry(springs).or_insert(0) += 1;
+        }
+        assert_eq!(seen.len(), 10);
+        assert!(seen.values().all(|&hits| (800..1200).contains(&hits)));
+        assert_eq!(Record::parse("#. 2").sample(next_u64), None);
+    }
+
+    fn grid(rows: &[&str]) -> Vec<Vec<char>> {
+        rows.iter().map(|row| row.chars().collect()).collect()
+    }
+
+    fn clues(lines: impl Iterator<Item = Vec<char>>) -> Vec<Vec<usize>> {
+        lines
+            .map(|line| line.split(|&c| c == '.').filter(|run| !run.is_empty()).map(<[char]>::len).collect())
+            .collect()
+    }
+
+    #[test]
+    fn it_solves_nonograms() {
+        let heart = grid(&[".#.#.", "#####", "#...#", ".###.", "..#.."]);
+        let columns = (0..5).map(|x| heart.iter().map(|row| row[x]).collect());
+        let nonogram = Nonogram::new(clues(heart.iter().cloned()), clues(columns));
+        assert_eq!(nonogram.rows, vec![vec![1, 1], vec![5], vec![1, 1], vec![3], vec![1]]);
+        assert_eq!(nonogram.columns, vec![vec![2], vec![2, 1], vec![1, 2], vec![2, 1], vec![2]]);
+        assert_eq!(nonogram.solve(), Solution::Unique(heart));
+
+        let mut partial = vec![vec!['?'; 5]; 5];
+        nonogram.propagate(&mut partial).unwrap();
+        assert_eq!(partial[1], vec!['#'; 5]);
+    }
+
+    #[test]
+    fn it_reports_ambiguity_and_contradictions() {
+        let diagonal = Nonogram::new(vec![vec![1], vec![1]], vec![vec![1], vec![1]]);
+        let Solution::Ambiguous([first, second]) = diagonal.solve() else { panic!() };
+        assert_eq!(first, grid(&["#.", ".#"]));
+        assert_eq!(second, grid(&[".#", "#."]));
+        let impossible = Nonogram::new(vec![vec![2], vec![]], vec![vec![1], vec![]]);
+        assert_eq!(impossible.solve(), Solution::Contradiction);
+        let empty = Nonogram::new(vec![vec![]; 2], vec![vec![]; 3]);
+        assert_eq!(empty.solve(), Solution::Unique(grid(&["...", "..."])));
+    }
+}
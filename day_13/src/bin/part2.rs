@@ -1,69 +1,16 @@
-use itertools::Itertools;
-
-fn _count_reflections_one_smudge(matrix: &[Vec<char>], len: usize) -> usize {
-    let mut res = 0;
-    'outer: for ((i, first), (j, second)) in matrix.iter().enumerate().tuple_windows() {
-        if first == second {
-            let mut fixed_smudge = false;
-            for counter in 1..len {
-                if j + counter < len && i >= counter {
-                    if matrix[i - counter] != matrix[j + counter] {
-                        if !fixed_smudge {
-                            if matrix[i - counter].iter().zip(matrix[j + counter].iter()).map(|(c1, c2)| (c1 != c2) as u8).sum::<u8>() == 1 {
-                                fixed_smudge = true;
-                            }
-                        } else {
-                            continue 'outer;
-                        }
-                    } 
-                } else if fixed_smudge {
-                    res += j;
-                    break 'outer;
-                } else {
-                    continue 'outer;
-                }
-            }
-        } else if first.iter().zip(second.iter()).map(|(c1, c2)| (c1 != c2) as u8).sum::<u8>() == 1  {
-            for counter in 1..len {
-                if j + counter < len && i >= counter {
-                    if matrix[i - counter] != matrix[j + counter] {
-                        continue 'outer;
-                    } 
-                } else {
-                    res += j;
-                    break 'outer;
-                }
-            }
-        }
-    }
-    res
-}
-
-fn _count_reflections(input: &str) -> usize {
-    let matrix: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-    let (x_len, y_len) = (matrix[0].len(), matrix.len());
-
-    let res_y = _count_reflections_one_smudge(&matrix, y_len) * 100;
-
-    let transposed_matrix = (0..x_len).map(|x| {
-        let matrix = &matrix;
-        (0..y_len).map(move |y| matrix[y][x]).collect::<Vec<char>>()
-    }).collect_vec();
-    let res_x = _count_reflections_one_smudge(&transposed_matrix, x_len);
-
-    res_x + res_y
-}
+use day_13::{analyse, total, Scoring};
 
 fn count_reflections(input: &str) -> usize {
-    input
-        .split("\n\n")
-        .flat_map(|block| block.split("\r\n\r\n"))
-        .map(_count_reflections)
-        .sum()
+    total(&analyse(input, 1), Scoring::Strict).unwrap()
 }
 
 fn main() {
     let input = include_str!("./input.txt");
+    if std::env::args().any(|arg| arg == "--report") {
+        for report in analyse(input, 1) {
+            println!("{report}");
+        }
+    }
     let res  = count_reflections(input);
     println!("{res}");
 }
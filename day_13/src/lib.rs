@@ -0,0 +1,333 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Orientation {
+    // Between two rows, mirroring the pattern top to bottom.
+    Horizontal,
+    // Between two columns, mirroring the pattern left to right.
+    Vertical,
+}
+
+// `before` is the number of rows (columns) above (left of) the axis; smudges are
+// the [x, y] cells on the near side whose mirror image differs from them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Axis {
+    pub orientation: Orientation,
+    pub before: usize,
+    pub smudges: Vec<[usize; 2]>,
+}
+
+impl Axis {
+    pub fn score(&self) -> usize {
+        match self.orientation {
+            Orientation::Horizontal => 100 * self.before,
+            Orientation::Vertical => self.before,
+        }
+    }
+}
+
+// Rows and columns as bitmasks split into 64-bit chunks, bit i % 64 of chunk
+// i / 64 set for a '#' at index i of the line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    pub rows: Vec<Vec<u64>>,
+    pub columns: Vec<Vec<u64>>,
+}
+
+fn count_differing(first: &[u64], second: &[u64]) -> usize {
+    first.iter().zip(second).map(|(a, b)| (a ^ b).count_ones() as usize).sum()
+}
+
+impl Pattern {
+    pub fn parse(block: &str) -> Self {
+        let grid: Vec<Vec<bool>> = block
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.chars().map(|c| c == '#').collect())
+            .collect();
+        let width = grid.first().map_or(0, Vec::len);
+        let mask = |cells: &mut dyn Iterator<Item = bool>| {
+            let mut chunks = vec![];
+            for (i, cell) in cells.enumerate() {
+                if i % 64 == 0 {
+                    chunks.push(0);
+                }
+                *chunks.last_mut().unwrap() |= u64::from(cell) << (i % 64);
+            }
+            chunks
+        };
+        let rows = grid.iter().map(|row| mask(&mut row.iter().copied())).collect();
+        let columns = (0..width).map(|x| mask(&mut grid.iter().map(|row| row[x]))).collect();
+        Pattern { rows, columns }
+    }
+
+    fn axes_of(lines: &[Vec<u64>], smudges: usize) -> Vec<(usize, Vec<[usize; 2]>)> {
+        (1..lines.len())
+            .filter_map(|before| {
+                let mut differing = 0;
+                let pairs = (0..before).rev().zip(before..lines.len());
+                for (near, far) in pairs.clone() {
+                    differing += count_differing(&lines[near], &lines[far]);
+                    if differing > smudges {
+                        return None;
+                    }
+                }
+                if differing != smudges {
+                    return None;
+                }
+                let cells = pairs
+                    .flat_map(|(near, far)| {
+                        let chunks = lines[near].iter().zip(&lines[far]).enumerate();
+                        chunks.flat_map(move |(chunk, (a, b))| {
+                            let diff = a ^ b;
+                            (0..64).filter(move |bit| diff >> bit & 1 == 1).map(move |bit| [chunk * 64 + bit, near])
+                        })
+                    })
+                    .collect();
+                Some((before, cells))
+            })
+            .collect()
+    }
+
+    // Every axis where exactly `smudges` cells differ from their mirror image.
+    pub fn axes(&self, smudges: usize) -> Vec<Axis> {
+        let horizontal = Self::axes_of(&self.rows, smudges).into_iter().map(|(before, cells)| Axis {
+            orientation: Orientation::Horizontal,
+            before,
+            smudges: cells,
+        });
+        let vertical = Self::axes_of(&self.columns, smudges).into_iter().map(|(before, cells)| Axis {
+            orientation: Orientation::Vertical,
+            before,
+            smudges: cells.into_iter().map(|[y, x]| [x, y]).collect(),
+        });
+        horizontal.chain(vertical).collect()
+    }
+}
+
+pub fn parse_patterns(input: &str) -> Vec<Pattern> {
+    input
+        .split("\n\n")
+        .flat_map(|block| block.split("\r\n\r\n"))
+        .filter(|block| !block.trim().is_empty())
+        .map(Pattern::parse)
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AxisError {
+    pub pattern: usize,
+    pub found: usize,
+}
+
+impl fmt::Display for AxisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pattern {} has {} reflection axes instead of one", self.pattern, self.found)
+    }
+}
+
+impl std::error::Error for AxisError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Scoring {
+    // Every axis counts.
+    All,
+    // Only the first horizontal and the first vertical axis count.
+    FirstPerOrientation,
+    // Only the first axis counts, horizontal ones before vertical ones.
+    First,
+    // Every pattern must have exactly one axis.
+    #[default]
+    Strict,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub pattern: usize,
+    pub axes: Vec<Axis>,
+}
+
+impl Report {
+    pub fn is_missing(&self) -> bool {
+        self.axes.is_empty()
+    }
+
+    pub fn is_ambiguous(&self) -> bool {
+        self.axes.len() > 1
+    }
+
+    pub fn score(&self, scoring: Scoring) -> Result<usize, AxisError> {
+        let first = |orientation| {
+            self.axes.iter().find(|axis| axis.orientation == orientation).map_or(0, Axis::score)
+        };
+        match scoring {
+            Scoring::All => Ok(self.axes.iter().map(Axis::score).sum()),
+            Scoring::FirstPerOrientation => Ok(first(Orientation::Horizontal) + first(Orientation::Vertical)),
+            Scoring::First => Ok(self.axes.first().map_or(0, Axis::score)),
+            Scoring::Strict => match self.axes.as_slice() {
+                [axis] => Ok(axis.score()),
+                axes => Err(AxisError { pattern: self.pattern, found: axes.len() }),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "pattern {:>3}:", self.pattern)?;
+        for axis in &self.axes {
+            let orientation = match axis.orientation {
+                Orientation::Horizontal => "horizontal",
+                Orientation::Vertical => "vertical",
+            };
+            write!(f, " {orientation} {}", axis.before)?;
+            for [x, y] in &axis.smudges {
+                write!(f, " smudge ({x}, {y})")?;
+            }
+            write!(f, ";")?;
+        }
+        if self.is_missing() {
+            write!(f, " no axis")?;
+        } else if self.is_ambiguous() {
+            write!(f, " multiple axes")?;
+        }
+        Ok(())
+    }
+}
+
+pub fn analyse(input: &str, smudges: usize) -> Vec<Report> {
+    parse_patterns(input)
+        .iter()
+        .enumerate()
+        .map(|(pattern, block)| Report { pattern, axes: block.axes(smudges) })
+        .collect()
+}
+
+pub fn total(reports: &[Report], scoring: Scoring) -> Result<usize, AxisError> {
+    reports.iter().map(|report| report.score(scoring)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_INPUT: &str =
+"#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#";
+
+    #[test]
+    fn it_encodes_bitmasks() {
+        let pattern = Pattern::parse("#.#\n..#");
+        assert_eq!(pattern.rows, vec![vec![0b101], vec![0b100]]);
+        assert_eq!(pattern.columns, vec![vec![0b01], vec![0b00], vec![0b11]]);
+    }
+
+    fn brute_force(grid: &[Vec<char>], smudges: usize) -> Vec<(Orientation, usize)> {
+        let (height, width) = (grid.len(), grid[0].len());
+        let horizontal = (1..height).filter(|&before| {
+            let pairs = (0..before).rev().zip(before..height);
+            pairs.flat_map(|(a, b)| (0..width).filter(move |&x| grid[a][x] != grid[b][x])).count() == smudges
+        });
+        let vertical = (1..width).filter(|&before| {
+            let pairs = (0..before).rev().zip(before..width);
+            pairs.flat_map(|(a, b)| (0..height).filter(move |&y| grid[y][a] != grid[y][b])).count() == smudges
+        });
+        horizontal
+            .map(|before| (Orientation::Horizontal, before))
+            .chain(vertical.map(|before| (Orientation::Vertical, before)))
+            .collect()
+    }
+
+    #[test]
+    fn it_handles_wide_patterns() {
+        let mut state = 0x9e37_79b9_u32;
+        let mut grid: Vec<Vec<char>> = (0..3)
+            .map(|_| {
+                let left: Vec<char> = (0..40)
+                    .map(|_| {
+                        state ^= state << 13;
+                        state ^= state >> 17;
+                        state ^= state << 5;
+                        if state & 1 == 1 { '#' } else { '.' }
+                    })
+                    .collect();
+                left.iter().chain(left.iter().rev()).copied().collect()
+            })
+            .collect();
+        grid[2][70] = if grid[2][70] == '#' { '.' } else { '#' };
+        let block = grid.iter().map(|row| row.iter().collect::<String>()).collect::<Vec<_>>().join("\n");
+        let pattern = Pattern::parse(&block);
+        assert_eq!(pattern.rows[0].len(), 2);
+        assert_eq!(pattern.columns.len(), 80);
+        for smudges in 0..4 {
+            let axes = pattern.axes(smudges).iter().map(|axis| (axis.orientation, axis.before)).collect::<Vec<_>>();
+            assert_eq!(axes, brute_force(&grid, smudges), "{smudges}");
+        }
+        let smudged = Axis { orientation: Orientation::Vertical, before: 40, smudges: vec![[9, 2]] };
+        assert!(pattern.axes(1).contains(&smudged));
+    }
+
+    #[test]
+    fn it_finds_axes_with_smudges() {
+        let patterns = parse_patterns(TEST_INPUT);
+        let clean = Axis { orientation: Orientation::Vertical, before: 5, smudges: vec![] };
+        assert_eq!(patterns[0].axes(0), vec![clean]);
+        let clean = Axis { orientation: Orientation::Horizontal, before: 4, smudges: vec![] };
+        assert_eq!(patterns[1].axes(0), vec![clean]);
+
+        let smudged = Axis { orientation: Orientation::Horizontal, before: 3, smudges: vec![[0, 0]] };
+        assert_eq!(patterns[0].axes(1), vec![smudged]);
+        let smudged = Axis { orientation: Orientation::Horizontal, before: 1, smudges: vec![[4, 0]] };
+        assert_eq!(patterns[1].axes(1), vec![smudged]);
+    }
+
+    #[test]
+    fn it_finds_every_axis() {
+        let pattern = Pattern::parse("##\n##");
+        let axes = pattern.axes(0);
+        assert_eq!(axes.len(), 2);
+        assert_eq!(axes.iter().map(Axis::score).sum::<usize>(), 101);
+        let pattern = Pattern::parse("##..\n....");
+        assert_eq!(pattern.axes(0).len(), 2);
+        assert_eq!(
+            pattern.axes(2),
+            vec![
+                Axis { orientation: Orientation::Horizontal, before: 1, smudges: vec![[0, 0], [1, 0]] },
+                Axis { orientation: Orientation::Vertical, before: 2, smudges: vec![[1, 0], [0, 0]] },
+            ]
+        );
+        assert!(Pattern::parse("#").axes(0).is_empty());
+    }
+
+    #[test]
+    fn it_reports_and_scores() {
+        let reports = analyse(TEST_INPUT, 0);
+        assert!(reports.iter().all(|report| !report.is_missing() && !report.is_ambiguous()));
+        assert_eq!(total(&reports, Scoring::Strict), Ok(405));
+        assert_eq!(total(&analyse(TEST_INPUT, 1), Scoring::default()), Ok(400));
+
+        let odd = analyse("##\n##\n\n#.\n..\n\n#..\n##.", 0);
+        assert_eq!(odd.iter().map(|report| report.axes.len()).collect::<Vec<_>>(), vec![2, 0, 0]);
+        assert!(odd[0].is_ambiguous() && odd[1].is_missing());
+        assert_eq!(total(&odd, Scoring::All), Ok(101));
+        assert_eq!(total(&odd, Scoring::FirstPerOrientation), Ok(101));
+        assert_eq!(total(&odd, Scoring::First), Ok(100));
+        assert_eq!(total(&odd, Scoring::Strict), Err(AxisError { pattern: 0, found: 2 }));
+        assert_eq!(odd[0].to_string(), "pattern   0: horizontal 1; vertical 1; multiple axes");
+        assert_eq!(odd[1].to_string(), "pattern   1: no axis");
+        assert_eq!(analyse(TEST_INPUT, 1)[1].to_string(), "pattern   1: horizontal 1 smudge (4, 0);");
+    }
+}